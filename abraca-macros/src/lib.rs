@@ -30,21 +30,70 @@ impl Parse for ClikeEnumInput {
     }
 }
 
+/// one parsed line of the input file: a variant name, an optional explicit
+/// `repr(u8)` discriminant, and an optional wire alias (e.g. an exchange's
+/// differently-cased channel string).
+struct ClikeEnumVariant {
+    ident: Ident,
+    discriminant: Option<u8>,
+    alias: Option<String>,
+}
+
 impl ClikeEnumInput {
+    fn parse_variants(content: &str) -> Vec<ClikeEnumVariant> {
+        content
+            .split('\n')
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let mut fields = line.splitn(2, ',');
+                let head = fields.next().unwrap().trim();
+                let alias = fields.next().map(|a| a.trim().to_owned());
+
+                let mut head_parts = head.splitn(2, '=');
+                let name = head_parts.next().unwrap().trim();
+                let discriminant = head_parts.next().map(|d| {
+                    d.trim()
+                        .parse::<u8>()
+                        .unwrap_or_else(|_| panic!("invalid discriminant for variant {name}: {d}"))
+                });
+
+                Some(ClikeEnumVariant {
+                    ident: Ident::new(name, proc_macro2::Span::call_site()),
+                    discriminant,
+                    alias,
+                })
+            })
+            .collect()
+    }
+
     fn expand(self) -> TokenStream2 {
         let enum_name = self.enum_name;
         let content = std::fs::read_to_string(self.file_name.value().as_str()).unwrap();
-        let variant_names = content.split('\n').map(|v| {
-            let ident = syn::Ident::new(v, proc_macro2::Span::call_site());
-            quote! {#ident,}
+        let variants = Self::parse_variants(&content);
+        let variant_tokens = variants.iter().map(|v| {
+            let ident = &v.ident;
+            let discriminant = v.discriminant.map(|d| quote! { = #d });
+            let alias_attr = v.alias.as_ref().map(|alias| {
+                quote! {
+                    #[serde(rename = #alias)]
+                    #[strum(serialize = #alias)]
+                }
+            });
+            quote! {
+                #alias_attr
+                #ident #discriminant,
+            }
         });
         quote!(
             #[repr(u8)]
-            #[derive(Debug, Default, Clone, PartialEq, Hash, serde::Deserialize, serde::Serialize, strum_macros::EnumString, strum_macros::Display)]
+            #[derive(Debug, Default, Clone, PartialEq, Hash, serde::Deserialize, serde::Serialize, strum_macros::EnumString, strum_macros::Display, strum_macros::EnumIter)]
             pub enum #enum_name {
                 #[default]
                 Unknown,
-                #(#variant_names)*
+                #(#variant_tokens)*
             }
         )
     }
@@ -25,14 +25,15 @@ impl Feature for MidPx {
         &self.name
     }
 
-    fn on_depth(&mut self, depth: &Depth) {
+    #[allow(unused_variables)]
+    fn on_depth(&mut self, depth: &Depth, snapshot: &FeatureSnapshot) {
         let px = (depth.asks[0].0 + depth.bids[0].0) / 2.0;
         self.value = Some(px);
         self.update_time = depth.exch_time;
     }
 
     #[allow(unused_variables)]
-    fn on_trade(&mut self, trade: &Trade) {}
+    fn on_trade(&mut self, trade: &Trade, snapshot: &FeatureSnapshot) {}
 
     fn value(&self) -> Option<f64> {
         self.value
@@ -77,9 +78,10 @@ impl Feature for EmaPx {
     }
 
     #[allow(unused_variables)]
-    fn on_depth(&mut self, depth: &Depth) {}
+    fn on_depth(&mut self, depth: &Depth, snapshot: &FeatureSnapshot) {}
 
-    fn on_trade(&mut self, trade: &Trade) {
+    #[allow(unused_variables)]
+    fn on_trade(&mut self, trade: &Trade, snapshot: &FeatureSnapshot) {
         if let Some(value) = self.value {
             let dt = (trade.exch_time - self.update_time).num_seconds();
             let w = (-LN_2 * dt as f64 / self.halflife as f64).exp();
@@ -99,6 +101,71 @@ impl Feature for EmaPx {
     }
 }
 
+/// a composite feature: the difference between two other named features,
+/// read through the [`FeatureSnapshot`] each feature is evaluated with.
+struct Spread {
+    name: String,
+    lhs: String,
+    rhs: String,
+    inst: Inst,
+    value: Option<f64>,
+    update_time: NaiveDateTime,
+}
+
+impl Spread {
+    fn new(lhs: &str, rhs: &str, inst: &Inst) -> Self {
+        Self {
+            // `lhs`/`rhs` are themselves feature names and may contain `_`
+            // (e.g. `EmaPx_Okx.BTC.USDT.Spot_10`), so they're joined with `|`
+            // rather than `_` to stay unambiguous to parse back out.
+            name: format!("Spread_{}|{}|{}", inst.to_string(), lhs, rhs),
+            lhs: lhs.to_owned(),
+            rhs: rhs.to_owned(),
+            inst: inst.clone(),
+            value: None,
+            update_time: NaiveDateTime::default(),
+        }
+    }
+
+    fn recompute(&mut self, snapshot: &FeatureSnapshot) {
+        if let (Some(lhs), Some(rhs)) = (snapshot.value_of(&self.lhs), snapshot.value_of(&self.rhs)) {
+            self.value = Some(lhs - rhs);
+        }
+    }
+}
+
+impl Feature for Spread {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_intrested(&self, inst: &Inst) -> bool {
+        self.inst == *inst
+    }
+
+    fn dependencies(&self) -> Vec<String> {
+        vec![self.lhs.clone(), self.rhs.clone()]
+    }
+
+    fn on_depth(&mut self, depth: &Depth, snapshot: &FeatureSnapshot) {
+        self.update_time = depth.exch_time;
+        self.recompute(snapshot);
+    }
+
+    fn on_trade(&mut self, trade: &Trade, snapshot: &FeatureSnapshot) {
+        self.update_time = trade.exch_time;
+        self.recompute(snapshot);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    fn update_time(&self) -> NaiveDateTime {
+        self.update_time
+    }
+}
+
 struct MyFeatureLib;
 
 impl FeatureLib for MyFeatureLib {
@@ -107,36 +174,43 @@ impl FeatureLib for MyFeatureLib {
     }
 
     fn create_feature(&self, name: &str) -> Option<Box<dyn Feature>> {
-        let mut parts = name.split('_');
-        let Some(fname) = parts.next() else{
-            return None;
-        };
-        let Some(inst) = parts.next() else{
-            return None;
-        };
-        let Ok(inst) = Inst::try_from(inst) else{
-            return None;
-        };
+        let (fname, rest) = name.split_once('_')?;
         match fname {
-            "MidPx" => return Some(Box::new(MidPx::new(&inst))),
+            "Spread" => {
+                // `inst` is `_`-free (it's dot-delimited) but `lhs`/`rhs` are
+                // themselves feature names that may contain `_`, so they're
+                // split on `|` instead, see `Spread::new`.
+                let mut parts = rest.splitn(3, '|');
+                let inst = parts.next()?;
+                let inst = Inst::try_from(inst).ok()?;
+                let lhs = parts.next()?;
+                let rhs = parts.next()?;
+                Some(Box::new(Spread::new(lhs, rhs, &inst)))
+            }
+            "MidPx" => {
+                let inst = Inst::try_from(rest).ok()?;
+                Some(Box::new(MidPx::new(&inst)))
+            }
             "EmaPx" => {
-                let Some(halflife) = parts.next() else {
-                    return  None;
-                };
-                let Ok(halflife) = halflife.parse::<i64>() else{
-                    return None;
-                  };
-                return Some(Box::new(EmaPx::new(&inst, halflife)));
+                let (inst, halflife) = rest.rsplit_once('_')?;
+                let inst = Inst::try_from(inst).ok()?;
+                let halflife = halflife.parse::<i64>().ok()?;
+                Some(Box::new(EmaPx::new(&inst, halflife)))
             }
-            _ => return None,
+            _ => None,
         }
     }
 }
 
-fn main() {
+fn main() -> Result<()> {
     let mut center = FeatureCenter::new(MyFeatureLib);
-    center.add_feature("MidPx_Okx.BTC.USDT.Spot");
-    center.add_feature("EmaPx_Okx.BTC.USDT.Spot_10");
-    center.add_feature("EmaPx_Okx.BTC.USDT.Spot_20");
+    center.add_feature("MidPx_Okx.BTC.USDT.Spot")?;
+    center.add_feature("EmaPx_Okx.BTC.USDT.Spot_10")?;
+    center.add_feature("EmaPx_Okx.BTC.USDT.Spot_20")?;
+    // depends on the two EmaPx features above; add_feature materializes
+    // them first if they aren't already present.
+    center.add_feature("Spread_Okx.BTC.USDT.Spot|EmaPx_Okx.BTC.USDT.Spot_10|EmaPx_Okx.BTC.USDT.Spot_20")?;
     println!("{:?}", center.id_map);
+    println!("{:?}", center.eval_order);
+    Ok(())
 }
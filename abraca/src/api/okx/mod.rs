@@ -1,22 +1,205 @@
 use crate::common::{
     defs::{DataType, Inst, Result},
-    msgs::{MsgReceiver, MsgSender},
-    traits::Api,
+    msgs::{AmendOrder, CancelOrder, ExecutionReport, Msg, MsgReceiver, MsgSender, NewOrder},
+    traits::{Api, Trader},
 };
-use ws::{PrivateClient, PublicClient, WsChannel, WsChannelArg};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, watch};
+use ws::{OrderCommand, PrivateClient, PublicClient, WsChannel, WsChannelArg};
+
+pub use ws::SubscriptionCommand;
 
 pub(self) mod parser;
+pub(self) mod rest;
 pub(self) mod ws;
 
+const SUBSCRIPTION_CHANNEL_BUFFER: usize = 64;
+const ORDER_CHANNEL_BUFFER: usize = 256;
+
 pub struct OkxApi {
     public_client: ws::PublicClient,
     private_client: Option<ws::PrivateClient>,
+    sub_tx: mpsc::Sender<SubscriptionCommand>,
+    trader_tx: Option<mpsc::Sender<OrderCommand>>,
+    next_cl_ord_id: Arc<AtomicI64>,
 }
 
 impl OkxApi {
     pub fn builder() -> WsClientBuilder {
         WsClientBuilder::new()
     }
+
+    /// returns a cloneable handle that lets callers change the live
+    /// subscription set without tearing down the socket.
+    pub fn subscription_handle(&self) -> SubscriptionHandle {
+        SubscriptionHandle {
+            tx: self.sub_tx.clone(),
+        }
+    }
+
+    /// returns a cloneable handle that lets callers place, cancel, and
+    /// amend orders over the authenticated connection. `None` unless
+    /// [`WsClientBuilder::credential`] was set, since the trading op
+    /// channel only exists on the private client.
+    pub fn trader(&self) -> Option<OkxTrader> {
+        self.trader_tx.clone().map(|tx| OkxTrader {
+            tx,
+            next_cl_ord_id: self.next_cl_ord_id.clone(),
+        })
+    }
+}
+
+/// lets a strategy place, cancel, and amend orders through a running
+/// [`OkxApi`] private client.
+#[derive(Clone)]
+pub struct OkxTrader {
+    tx: mpsc::Sender<OrderCommand>,
+    /// generates the `cl_ord_id` stamped onto every order this trader
+    /// places, so two orders submitted within the same second never
+    /// collide. Shared with every clone of this trader rather than
+    /// reseeded per-clone.
+    next_cl_ord_id: Arc<AtomicI64>,
+}
+
+impl OkxTrader {
+    fn next_cl_ord_id(&self) -> i64 {
+        self.next_cl_ord_id.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+impl Trader for OkxTrader {
+    async fn place_order(&self, mut order: NewOrder) -> Result<ExecutionReport> {
+        order.cl_ord_id = self.next_cl_ord_id();
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.tx.send(OrderCommand::NewOrder(order, ack_tx)).await?;
+        Ok(ack_rx.await?)
+    }
+
+    async fn cancel_order(&self, order: CancelOrder) -> Result<()> {
+        self.tx.send(OrderCommand::CancelOrder(order)).await?;
+        Ok(())
+    }
+
+    async fn amend_order(&self, amend: AmendOrder) -> Result<ExecutionReport> {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.tx
+            .send(OrderCommand::AmendOrder(amend, ack_tx))
+            .await?;
+        Ok(ack_rx.await?)
+    }
+}
+
+/// lets a running [`OkxApi`] add or drop instrument/data-type subscriptions
+/// on its public websocket while it keeps the book state it already has.
+///
+/// This is the one runtime subscribe/unsubscribe path: it's driven by its
+/// own `sub_tx`/`sub_rx` pair rather than `Msg::Subscribe`/`Msg::Unsubscribe`
+/// over the shared [`MsgReceiver`], because only [`PrivateClient`] is ever
+/// handed that `rx` — [`PublicClient`] has no way to observe it, so routing
+/// public-channel changes through it would mean relaying them back out to
+/// the public client over some other channel anyway. There's also nothing
+/// for [`PrivateClient`] to subscribe/unsubscribe at runtime: its channels
+/// (`BalanceAndPosition`/`Orders`/`Positions`) are account-scoped, not
+/// per-instrument, and fixed for the life of the connection.
+#[derive(Clone)]
+pub struct SubscriptionHandle {
+    tx: mpsc::Sender<SubscriptionCommand>,
+}
+
+impl SubscriptionHandle {
+    /// sends every `(Inst, DataType)` pair as a single `subscribe` frame.
+    pub async fn subscribe(&self, subs: impl IntoIterator<Item = (Inst, DataType)>) -> Result<()> {
+        self.tx
+            .send(SubscriptionCommand::Subscribe(subs.into_iter().collect()))
+            .await?;
+        Ok(())
+    }
+
+    /// sends every `(Inst, DataType)` pair as a single `unsubscribe` frame.
+    pub async fn unsubscribe(
+        &self,
+        subs: impl IntoIterator<Item = (Inst, DataType)>,
+    ) -> Result<()> {
+        self.tx
+            .send(SubscriptionCommand::Unsubscribe(subs.into_iter().collect()))
+            .await?;
+        Ok(())
+    }
+}
+
+/// watches `path`, a newline-delimited `<inst>,<data_type>` channels config
+/// (e.g. `Okx.BTC.USDT.Spot,Depth`), and applies only the delta to `handle`
+/// whenever the file's contents change.
+pub async fn watch_channels_file(
+    path: impl AsRef<Path>,
+    handle: SubscriptionHandle,
+    poll_interval: Duration,
+) -> Result<()> {
+    let path = path.as_ref();
+    let mut current: HashSet<(Inst, DataType)> = HashSet::new();
+    let mut interval = tokio::time::interval(poll_interval);
+    loop {
+        interval.tick().await;
+        let desired = match read_channels_file(path) {
+            Ok(desired) => desired,
+            Err(e) => {
+                log::warn!("failed to read channels config {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let added: Vec<_> = desired.difference(&current).cloned().collect();
+        if !added.is_empty() {
+            handle.subscribe(added).await?;
+        }
+        let removed: Vec<_> = current.difference(&desired).cloned().collect();
+        if !removed.is_empty() {
+            handle.unsubscribe(removed).await?;
+        }
+        current = desired;
+    }
+}
+
+fn read_channels_file(path: &Path) -> Result<HashSet<(Inst, DataType)>> {
+    let content = std::fs::read_to_string(path)?;
+    let mut entries = HashSet::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((inst_str, data_type_str)) = line.split_once(',') else {
+            log::warn!("skipping malformed channels config line: {}", line);
+            continue;
+        };
+        let (Ok(inst), Ok(data_type)) = (
+            Inst::try_from(inst_str.trim()),
+            DataType::try_from(data_type_str.trim()),
+        ) else {
+            log::warn!("skipping unrecognized channels config line: {}", line);
+            continue;
+        };
+        entries.insert((inst, data_type));
+    }
+    Ok(entries)
+}
+
+/// watches `rx` for [`Msg::SigTerm`] (or a dropped sender) and signals
+/// `shutdown_tx`, so a public-only [`OkxApi`] can still shut down gracefully
+/// even though it has no private client around to consume `rx` itself.
+async fn forward_shutdown(mut rx: MsgReceiver, shutdown_tx: watch::Sender<bool>) {
+    loop {
+        match rx.recv().await {
+            Some(Msg::SigTerm) | None => {
+                let _ = shutdown_tx.send(true);
+                return;
+            }
+            Some(_) => (),
+        }
+    }
 }
 
 impl Api for OkxApi {
@@ -26,27 +209,48 @@ impl Api for OkxApi {
 
     async fn start(self, tx: MsgSender, rx: MsgReceiver) -> Result<()> {
         log::info!("start okx websocket client");
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
         if let Some(private_client) = self.private_client {
             log::info!("start okx private websocket client");
             let tx = tx.clone();
+            let shutdown_tx = shutdown_tx.clone();
+            let shutdown_rx = shutdown_rx.clone();
             tokio::spawn(async move {
                 private_client
-                    .start(tx, rx)
+                    .start(tx, rx, shutdown_tx, shutdown_rx)
                     .await
                     .expect("start okx private websocket client error");
             });
+        } else {
+            // no private client to consume `rx` for trading requests, so watch
+            // it here instead, purely for `Msg::SigTerm`/a dropped sender.
+            tokio::spawn(forward_shutdown(rx, shutdown_tx));
         }
         log::info!("start okx public websocket client");
-        self.public_client.start(tx).await
+        self.public_client.start(tx, shutdown_rx).await
     }
 }
 
-#[derive(Default)]
 pub struct WsClientBuilder {
     apikey: String,
     secretkey: String,
     passphrase: String,
     channels: Vec<WsChannelArg>,
+    heartbeat_interval: Duration,
+    heartbeat_timeout: Duration,
+}
+
+impl Default for WsClientBuilder {
+    fn default() -> Self {
+        Self {
+            apikey: String::new(),
+            secretkey: String::new(),
+            passphrase: String::new(),
+            channels: Vec::new(),
+            heartbeat_interval: ws::DEFAULT_HEARTBEAT_INTERVAL,
+            heartbeat_timeout: ws::DEFAULT_HEARTBEAT_TIMEOUT,
+        }
+    }
 }
 
 impl WsClientBuilder {
@@ -54,15 +258,34 @@ impl WsClientBuilder {
         Self::default()
     }
 
+    /// overrides the client-side keepalive ping cadence (`interval`) and how
+    /// long to wait for a `pong` before the socket is considered dead and
+    /// reconnected (`timeout`). Defaults to
+    /// [`ws::DEFAULT_HEARTBEAT_INTERVAL`]/[`ws::DEFAULT_HEARTBEAT_TIMEOUT`].
+    pub fn heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
     pub fn build(self) -> OkxApi {
+        let (sub_tx, sub_rx) = mpsc::channel(SUBSCRIPTION_CHANNEL_BUFFER);
         if self.apikey.is_empty() || self.secretkey.is_empty() || self.passphrase.is_empty() {
             OkxApi {
                 public_client: PublicClient {
                     channels: self.channels,
+                    sub_rx: Some(sub_rx),
+                    heartbeat_interval: self.heartbeat_interval,
+                    heartbeat_timeout: self.heartbeat_timeout,
                 },
                 private_client: None,
+                sub_tx,
+                trader_tx: None,
+                next_cl_ord_id: Arc::new(AtomicI64::new(chrono::Utc::now().timestamp_millis())),
             }
         } else {
+            let (order_tx, order_rx) = mpsc::channel(ORDER_CHANNEL_BUFFER);
+            let pending_acks = Arc::new(Mutex::new(HashMap::new()));
             let private_channels = vec![
                 WsChannelArg {
                     channel: WsChannel::BalanceAndPosition,
@@ -86,13 +309,23 @@ impl WsClientBuilder {
             OkxApi {
                 public_client: PublicClient {
                     channels: self.channels,
+                    sub_rx: Some(sub_rx),
+                    heartbeat_interval: self.heartbeat_interval,
+                    heartbeat_timeout: self.heartbeat_timeout,
                 },
                 private_client: Some(PrivateClient {
                     apikey: self.apikey,
                     secretkey: self.secretkey,
                     passphrase: self.passphrase,
                     channels: private_channels,
+                    order_rx: Some(order_rx),
+                    pending_acks,
+                    heartbeat_interval: self.heartbeat_interval,
+                    heartbeat_timeout: self.heartbeat_timeout,
                 }),
+                sub_tx,
+                trader_tx: Some(order_tx),
+                next_cl_ord_id: Arc::new(AtomicI64::new(chrono::Utc::now().timestamp_millis())),
             }
         }
     }
@@ -113,15 +346,8 @@ impl WsClientBuilder {
         for (i, d) in subs {
             if let Ok(inst) = i.try_into() {
                 if let Ok(data_type) = d.try_into() {
-                    let channel = match data_type {
-                        DataType::Depth => WsChannel::Books5,
-                        DataType::Trade => WsChannel::Trade,
-                        DataType::Ticker => WsChannel::Tickers,
-                        DataType::OpenInterest => WsChannel::OpenInterest,
-                        DataType::FundingRate => WsChannel::FundingRate,
-                    };
                     self.channels.push(WsChannelArg {
-                        channel,
+                        channel: ws::channel_for_data_type(data_type),
                         inst_id: Some(parser::inst_to_str(&inst)),
                         inst_type: None,
                         inst_family: None,
@@ -1,135 +1,198 @@
 use crate::prelude::*;
-use anyhow::Ok;
+use anyhow::{anyhow, Ok};
 use chrono::NaiveDateTime;
 use serde_json::Value;
 
+/// `v[field]` as a `&str`, with a descriptive error (naming the field and
+/// the raw JSON it came from) if the field is missing or isn't a string.
+/// OKX sends every scalar as a JSON string, so this covers the common case;
+/// see [`get_f64`]/[`get_ts`] for the parsed forms used everywhere else.
+fn get_str<'a>(v: &'a Value, field: &str) -> Result<&'a str> {
+    v[field]
+        .as_str()
+        .ok_or_else(|| anyhow!("missing or non-string field {field:?} in {v}"))
+}
+
+/// `v[field]` parsed as an `f64`.
+fn get_f64(v: &Value, field: &str) -> Result<f64> {
+    get_str(v, field)?
+        .parse()
+        .map_err(|e| anyhow!("invalid f64 in field {field:?} of {v}: {e}"))
+}
+
+/// `v[field]` parsed as a millisecond-since-epoch timestamp.
+fn get_ts(v: &Value, field: &str) -> Result<NaiveDateTime> {
+    str_to_naive_datetime(get_str(v, field)?)
+}
+
 pub fn parse_ticker(v: &Value) -> Result<Ticker> {
     Ok(Ticker {
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        exch_time: str_to_naive_datetime(v["ts"].as_str().unwrap()),
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        exch_time: get_ts(v, "ts")?,
         recv_time: chrono::Utc::now().naive_utc(),
-        last: v["last"].as_str().unwrap().parse()?,
-        last_sz: v["lastSz"].as_str().unwrap().parse()?,
-        ask_px: v["askPx"].as_str().unwrap().parse()?,
-        ask_sz: v["askSz"].as_str().unwrap().parse()?,
-        bid_px: v["bidPx"].as_str().unwrap().parse()?,
-        bid_sz: v["bidSz"].as_str().unwrap().parse()?,
+        last: get_f64(v, "last")?,
+        last_sz: get_f64(v, "lastSz")?,
+        ask_px: get_f64(v, "askPx")?,
+        ask_sz: get_f64(v, "askSz")?,
+        bid_px: get_f64(v, "bidPx")?,
+        bid_sz: get_f64(v, "bidSz")?,
     })
 }
 
 pub fn parse_funding_rate(v: &Value) -> Result<FundingRate> {
     Ok(FundingRate {
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
+        inst: str_to_inst(get_str(v, "instId")?)?,
         recv_time: chrono::Utc::now().naive_utc(),
-        funding_rate: v["fundingRate"].as_str().unwrap().parse()?,
-        next_funding_rate: v["nextFundingRate"].as_str().unwrap().parse()?,
-        funding_time: str_to_naive_datetime(v["fundingTime"].as_str().unwrap()),
-        next_funding_time: str_to_naive_datetime(v["nextFundingTime"].as_str().unwrap()),
+        funding_rate: get_f64(v, "fundingRate")?,
+        next_funding_rate: get_f64(v, "nextFundingRate")?,
+        funding_time: get_ts(v, "fundingTime")?,
+        next_funding_time: get_ts(v, "nextFundingTime")?,
     })
 }
 
 pub fn parse_open_interest(v: &Value) -> Result<OpenInterest> {
     Ok(OpenInterest {
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        exch_time: str_to_naive_datetime(v["ts"].as_str().unwrap()),
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        exch_time: get_ts(v, "ts")?,
         recv_time: chrono::Utc::now().naive_utc(),
-        oi: v["oi"].as_str().unwrap().parse()?,
-        oi_ccy: v["oiCcy"].as_str().unwrap().parse()?,
+        oi: get_f64(v, "oi")?,
+        oi_ccy: get_f64(v, "oiCcy")?,
     })
 }
 
+/// a single `[px, sz, numOrders, numContracts]` level out of a `books5`
+/// `asks`/`bids` array, parsed into the `(px, sz)` pair [`Depth`] keeps,
+/// alongside the raw `px`/`sz` strings as OKX sent them (needed to compute
+/// [`books5_checksum`], which must hash OKX's exact wire text rather than a
+/// reformatted float).
+fn get_level(v: &Value, side: &str, idx: usize) -> Result<(f64, f64, String, String)> {
+    let level = &v[side][idx];
+    let px_str = level[0]
+        .as_str()
+        .ok_or_else(|| anyhow!("missing or non-string {side}[{idx}][0] in {v}"))?
+        .to_owned();
+    let sz_str = level[1]
+        .as_str()
+        .ok_or_else(|| anyhow!("missing or non-string {side}[{idx}][1] in {v}"))?
+        .to_owned();
+    let px = px_str
+        .parse()
+        .map_err(|e| anyhow!("invalid px in {side}[{idx}] of {v}: {e}"))?;
+    let sz = sz_str
+        .parse()
+        .map_err(|e| anyhow!("invalid sz in {side}[{idx}] of {v}: {e}"))?;
+    Ok((px, sz, px_str, sz_str))
+}
+
+/// OKX's `books5` checksum: interleave `bidPx:bidSz:askPx:askSz` for the
+/// first `levels` levels, join with `:`, CRC32 (IEEE) the UTF-8 bytes, and
+/// reinterpret the unsigned result as `i32`. Takes the raw wire strings
+/// rather than the parsed `f64`s, since re-stringifying a float can drop
+/// formatting OKX's own checksum depends on (e.g. `"57745.50"` round-trips
+/// through `f64` as `"57745.5"`).
+fn books5_checksum(raw_bids: &[(String, String)], raw_asks: &[(String, String)], levels: usize) -> i32 {
+    let depth = raw_bids.len().min(raw_asks.len()).min(levels);
+    let mut parts = Vec::with_capacity(depth * 4);
+    for i in 0..depth {
+        parts.push(raw_bids[i].0.as_str());
+        parts.push(raw_bids[i].1.as_str());
+        parts.push(raw_asks[i].0.as_str());
+        parts.push(raw_asks[i].1.as_str());
+    }
+    crc32fast::hash(parts.join(":").as_bytes()) as i32
+}
+
 pub fn parse_books5(v: &Value) -> Result<Depth> {
+    let n_asks = v["asks"].as_array().map(Vec::len).unwrap_or_default().min(5);
+    let n_bids = v["bids"].as_array().map(Vec::len).unwrap_or_default().min(5);
     let mut asks = [(0.0, 0.0); 5];
     let mut bids = [(0.0, 0.0); 5];
-    v["asks"]
-        .as_array()
-        .unwrap()
-        .iter()
-        .enumerate()
-        .take(5)
-        .for_each(|(i, a)| {
-            asks[i] = (
-                a[0].as_str().unwrap().parse().unwrap(),
-                a[1].as_str().unwrap().parse().unwrap(),
-            );
-        });
-    v["bids"]
-        .as_array()
-        .unwrap()
-        .iter()
-        .enumerate()
-        .take(5)
-        .for_each(|(i, b)| {
-            bids[i] = (
-                b[0].as_str().unwrap().parse().unwrap(),
-                b[1].as_str().unwrap().parse().unwrap(),
-            );
-        });
-    Ok(Depth {
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        exch_time: str_to_naive_datetime(v["ts"].as_str().unwrap()),
+    let mut raw_asks = Vec::with_capacity(n_asks);
+    let mut raw_bids = Vec::with_capacity(n_bids);
+    for i in 0..n_asks {
+        let (px, sz, px_str, sz_str) = get_level(v, "asks", i)?;
+        asks[i] = (px, sz);
+        raw_asks.push((px_str, sz_str));
+    }
+    for i in 0..n_bids {
+        let (px, sz, px_str, sz_str) = get_level(v, "bids", i)?;
+        bids[i] = (px, sz);
+        raw_bids.push((px_str, sz_str));
+    }
+    let depth = Depth {
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        exch_time: get_ts(v, "ts")?,
         recv_time: chrono::Utc::now().naive_utc(),
         asks,
         bids,
-    })
+    };
+    if let Some(checksum) = v["checksum"].as_i64() {
+        if books5_checksum(&raw_bids, &raw_asks, 5) != checksum as i32 {
+            return Err(anyhow!("books5 checksum mismatch for {:?}", depth.inst));
+        }
+    }
+    Ok(depth)
 }
 
 pub fn parse_trade(v: &Value) -> Result<Trade> {
     Ok(Trade {
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        exch_time: str_to_naive_datetime(v["ts"].as_str().unwrap()),
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        exch_time: get_ts(v, "ts")?,
         recv_time: chrono::Utc::now().naive_utc(),
-        side: str_to_side(v["side"].as_str().unwrap()),
-        px: v["px"].as_str().unwrap().parse()?,
-        sz: v["sz"].as_str().unwrap().parse()?,
+        side: str_to_side(get_str(v, "side")?)?,
+        px: get_f64(v, "px")?,
+        sz: get_f64(v, "sz")?,
     })
 }
 
 pub fn parse_order(v: &Value) -> Result<ExecutionReport> {
     Ok(ExecutionReport {
-        c_time: str_to_naive_datetime(v["cTime"].as_str().unwrap()),
-        u_time: str_to_naive_datetime(v["uTime"].as_str().unwrap()),
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        ord_id: v["ordId"].as_str().unwrap().parse()?,
-        cl_ord_id: v["clOrdId"].as_str().unwrap().parse()?,
-        px: v["px"].as_str().unwrap().parse()?,
-        sz: v["sz"].as_str().unwrap().parse()?,
-        notional_usd: v["notionalUsd"].as_str().unwrap().parse()?,
-        ord_type: str_to_ord_type(v["ordType"].as_str().unwrap()),
-        side: str_to_side(v["side"].as_str().unwrap()),
-        fill_px: v["fillPx"].as_str().unwrap().parse()?,
-        fill_sz: v["fillSz"].as_str().unwrap().parse()?,
-        acc_fill_sz: v["accFillSz"].as_str().unwrap().parse()?,
-        avg_px: v["avgPx"].as_str().unwrap().parse()?,
-        state: str_to_ord_state(v["state"].as_str().unwrap()),
-        lever: v["lever"].as_str().unwrap().parse()?,
-        fee: v["fee"].as_str().unwrap().parse()?,
+        c_time: get_ts(v, "cTime")?,
+        u_time: get_ts(v, "uTime")?,
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        ord_id: get_str(v, "ordId")?.parse()?,
+        cl_ord_id: get_str(v, "clOrdId")?.parse()?,
+        px: get_f64(v, "px")?,
+        sz: get_f64(v, "sz")?,
+        notional_usd: get_f64(v, "notionalUsd")?,
+        ord_type: str_to_ord_type(v)?,
+        side: str_to_side(get_str(v, "side")?)?,
+        fill_px: get_f64(v, "fillPx")?,
+        fill_sz: get_f64(v, "fillSz")?,
+        acc_fill_sz: get_f64(v, "accFillSz")?,
+        avg_px: get_f64(v, "avgPx")?,
+        state: str_to_ord_state(get_str(v, "state")?),
+        lever: get_f64(v, "lever")?,
+        fee: get_f64(v, "fee")?,
     })
 }
 
 pub fn parse_position(v: &Value) -> Result<PositionReport> {
     Ok(PositionReport {
-        u_time: str_to_naive_datetime(v["uTime"].as_str().unwrap()),
-        inst: str_to_inst(v["instId"].as_str().unwrap()),
-        mgn_mode: str_to_mgn_mode(v["mgnMode"].as_str().unwrap()),
-        pos: v["pos"].as_str().unwrap().parse()?,
-        ccy: v["ccy"].as_str().unwrap().try_into()?,
-        pos_ccy: v["posCcy"].as_str().unwrap().parse()?,
-        avg_px: v["avgPx"].as_str().unwrap().parse()?,
+        u_time: get_ts(v, "uTime")?,
+        inst: str_to_inst(get_str(v, "instId")?)?,
+        mgn_mode: str_to_mgn_mode(get_str(v, "mgnMode")?)?,
+        pos: get_f64(v, "pos")?,
+        ccy: get_str(v, "ccy")?.try_into()?,
+        pos_ccy: get_str(v, "posCcy")?.parse()?,
+        avg_px: get_f64(v, "avgPx")?,
     })
 }
 
 pub fn parse_balance_and_position(v: &Value) -> Result<BalanceReport> {
     Ok(BalanceReport {
-        u_time: str_to_naive_datetime(v["uTime"].as_str().unwrap()),
+        u_time: get_ts(v, "uTime")?,
         exch: Exch::Okx,
-        ccy: v["ccy"].as_str().unwrap().try_into()?,
-        cash_bal: v["cashBal"].as_str().unwrap().parse()?,
+        ccy: get_str(v, "ccy")?.try_into()?,
+        cash_bal: get_f64(v, "cashBal")?,
     })
 }
 
-fn str_to_inst(s: &str) -> Inst {
+pub(crate) fn str_to_inst(s: &str) -> Result<Inst> {
     let parts: Vec<&str> = s.split('-').collect();
+    if parts.len() < 2 {
+        return Err(anyhow!("malformed instrument id {s:?}"));
+    }
     let base_ccy: Ccy = parts[0].try_into().unwrap_or_default();
     let quote_ccy: Ccy = parts[1].try_into().unwrap_or_default();
     let inst_type = match parts.len() {
@@ -138,62 +201,98 @@ fn str_to_inst(s: &str) -> Inst {
             if parts[2] == "SWAP" {
                 InstType::Swap
             } else {
-                format!("Futures-{}", parts[2]).as_str().try_into().unwrap()
+                format!("Futures-{}", parts[2])
+                    .as_str()
+                    .try_into()
+                    .map_err(|e: String| anyhow!("malformed instrument id {s:?}: {e}"))?
             }
         }
         5 => format!("Options-{}-{}-{}", parts[2], parts[3], parts[4])
             .as_str()
             .try_into()
-            .unwrap(),
-        _ => unreachable!(),
+            .map_err(|e: String| anyhow!("malformed instrument id {s:?}: {e}"))?,
+        _ => return Err(anyhow!("malformed instrument id {s:?}")),
     };
-    Inst {
+    Ok(Inst {
         exch: Exch::Okx,
         base_ccy,
         quote_ccy,
         inst_type,
-    }
+    })
 }
 
-fn str_to_naive_datetime(s: &str) -> NaiveDateTime {
-    NaiveDateTime::from_timestamp_millis(s.parse().unwrap_or_default()).unwrap()
+pub(crate) fn str_to_naive_datetime(s: &str) -> Result<NaiveDateTime> {
+    let ms: i64 = s
+        .parse()
+        .map_err(|e| anyhow!("invalid timestamp {s:?}: {e}"))?;
+    NaiveDateTime::from_timestamp_millis(ms).ok_or_else(|| anyhow!("timestamp {ms} out of range"))
 }
 
-fn str_to_mgn_mode(s: &str) -> MgnMode {
+fn str_to_mgn_mode(s: &str) -> Result<MgnMode> {
     match s {
-        "cross" => MgnMode::Cross,
-        "isolated" => MgnMode::Isolated,
-        "cash" => MgnMode::Cash,
-        _ => unreachable!(),
+        "cross" => Ok(MgnMode::Cross),
+        "isolated" => Ok(MgnMode::Isolated),
+        "cash" => Ok(MgnMode::Cash),
+        _ => Err(anyhow!("unrecognized margin mode {s:?}")),
     }
 }
 
-fn str_to_ord_type(s: &str) -> OrdType {
-    match s {
+/// takes the whole execution report `v` rather than just `v["ordType"]`,
+/// since the algo order types carry extra fields (`triggerPx`/`orderPx`/
+/// `callbackRatio`/`callbackSpread`) that live alongside it.
+fn str_to_ord_type(v: &Value) -> Result<OrdType> {
+    Ok(match get_str(v, "ordType")? {
         "market" => OrdType::Market,
         "limit" => OrdType::Limit,
         "post_only" => OrdType::PostOnly,
         "fok" => OrdType::Fok,
         "ioc" => OrdType::Ioc,
-        _ => unreachable!(),
-    }
+        "conditional" => OrdType::TriggerLimit {
+            trigger_px: get_f64(v, "triggerPx")?,
+            ord_px: get_f64(v, "orderPx")?,
+        },
+        "trigger" => OrdType::TriggerMarket {
+            trigger_px: get_f64(v, "triggerPx")?,
+        },
+        "move_order_stop" => OrdType::TrailingStop {
+            callback_ratio: v["callbackRatio"]
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| anyhow!("invalid callbackRatio in {v}: {e}"))?,
+            callback_spread: v["callbackSpread"]
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|e| anyhow!("invalid callbackSpread in {v}: {e}"))?,
+        },
+        other => return Err(anyhow!("unrecognized order type {other:?} in {v}")),
+    })
 }
 
-fn str_to_side(s: &str) -> Side {
+fn str_to_side(s: &str) -> Result<Side> {
     match s {
-        "buy" => Side::Buy,
-        "sell" => Side::Sell,
-        _ => unreachable!(),
+        "buy" => Ok(Side::Buy),
+        "sell" => Ok(Side::Sell),
+        _ => Err(anyhow!("unrecognized side {s:?}")),
     }
 }
 
+/// unrecognized order states (OKX periodically adds new ones, e.g.
+/// `mmp_canceled`) fall back to [`OrdState::Unknwon`] instead of erroring,
+/// so a strategy still sees the report rather than losing it entirely.
 fn str_to_ord_state(s: &str) -> OrdState {
     match s {
         "live" => OrdState::Live,
         "filled" => OrdState::Filled,
         "canceled" => OrdState::Canceled,
         "partially_filled" => OrdState::PartiallyFilled,
-        _ => unreachable!(),
+        other => {
+            log::warn!("unrecognized order state {other:?}, treating as unknown");
+            OrdState::Unknwon
+        }
     }
 }
 
@@ -245,6 +344,9 @@ pub fn ord_type_to_str(ord_type: &OrdType) -> &'static str {
         OrdType::PostOnly => "post_only",
         OrdType::Fok => "fok",
         OrdType::Ioc => "ioc",
+        OrdType::TriggerLimit { .. } => "conditional",
+        OrdType::TriggerMarket { .. } => "trigger",
+        OrdType::TrailingStop { .. } => "move_order_stop",
     }
 }
 
@@ -289,6 +391,14 @@ mod tests {
         assert_eq!(ticker.bid_sz, 5.0);
     }
 
+    #[test]
+    fn parse_ticker_reports_missing_field() {
+        let s = r#"{"instId": "LTC-USD-SWAP", "ts": "1597026383085"}"#;
+        let v: Value = serde_json::from_str(s).unwrap();
+        let err = parse_ticker(&v).unwrap_err();
+        assert!(err.to_string().contains("last"));
+    }
+
     #[test]
     fn parse_funding_rate_works() {
         let s = r#"
@@ -360,6 +470,84 @@ mod tests {
         assert_eq!(books5.bids[0].1, 57745.0);
     }
 
+    #[test]
+    fn parse_books5_accepts_matching_checksum() {
+        let s = r#"
+        {
+            "asks": [
+              ["111.06","55154","0","2"],
+              ["111.07","53276","0","2"],
+              ["111.08","72435","0","2"],
+              ["111.09","70312","0","2"],
+              ["111.1","67272","0","2"]],
+            "bids": [
+              ["111.05","57745","0","2"],
+              ["111.04","57109","0","2"],
+              ["111.03","69563","0","2"],
+              ["111.02","71248","0","2"],
+              ["111.01","65090","0","2"]],
+            "instId": "BCH-USDT-SWAP",
+            "ts": "1670324386802",
+            "checksum": -1143374153
+        }"#;
+        let v: Value = serde_json::from_str(s).unwrap();
+        assert!(parse_books5(&v).is_ok());
+    }
+
+    #[test]
+    fn parse_books5_rejects_mismatched_checksum() {
+        let s = r#"
+        {
+            "asks": [
+              ["111.06","55154","0","2"],
+              ["111.07","53276","0","2"],
+              ["111.08","72435","0","2"],
+              ["111.09","70312","0","2"],
+              ["111.1","67272","0","2"]],
+            "bids": [
+              ["111.05","57745","0","2"],
+              ["111.04","57109","0","2"],
+              ["111.03","69563","0","2"],
+              ["111.02","71248","0","2"],
+              ["111.01","65090","0","2"]],
+            "instId": "BCH-USDT-SWAP",
+            "ts": "1670324386802",
+            "checksum": 0
+        }"#;
+        let v: Value = serde_json::from_str(s).unwrap();
+        assert!(parse_books5(&v).is_err());
+    }
+
+    #[test]
+    fn parse_books5_checksum_uses_raw_strings_not_reformatted_floats() {
+        // bid size "57745.50" parses to the same f64 as "57745" would, but
+        // re-stringifying it loses the trailing zero OKX's own checksum was
+        // computed against. If this hashed `f64::to_string()` instead of the
+        // raw wire string, this checksum (computed against "57745.50") would
+        // be rejected as a mismatch.
+        let s = r#"
+        {
+            "asks": [
+              ["111.06","55154","0","2"],
+              ["111.07","53276","0","2"],
+              ["111.08","72435","0","2"],
+              ["111.09","70312","0","2"],
+              ["111.1","67272","0","2"]],
+            "bids": [
+              ["111.05","57745.50","0","2"],
+              ["111.04","57109","0","2"],
+              ["111.03","69563","0","2"],
+              ["111.02","71248","0","2"],
+              ["111.01","65090","0","2"]],
+            "instId": "BCH-USDT-SWAP",
+            "ts": "1670324386802",
+            "checksum": 539939485
+        }"#;
+        let v: Value = serde_json::from_str(s).unwrap();
+        let books5 = parse_books5(&v).unwrap();
+        assert_eq!(books5.bids[0].1, 57745.5);
+    }
+
     #[test]
     fn parse_trade_works() {
         let s = r#"
@@ -378,4 +566,24 @@ mod tests {
         assert_eq!(trade.sz, 0.12060306);
         assert_eq!(trade.side, Side::Buy);
     }
+
+    #[test]
+    fn parse_trade_rejects_unrecognized_side() {
+        let s = r#"
+        {
+            "instId": "BTC-USDT",
+            "px": "42219.9",
+            "sz": "0.12060306",
+            "side": "buyy",
+            "ts": "1630048897897"
+        }"#;
+        let v: Value = serde_json::from_str(s).unwrap();
+        assert!(parse_trade(&v).is_err());
+    }
+
+    #[test]
+    fn str_to_ord_state_falls_back_to_unknown() {
+        assert_eq!(str_to_ord_state("mmp_canceled"), OrdState::Unknwon);
+        assert_eq!(str_to_ord_state("live"), OrdState::Live);
+    }
 }
@@ -1,8 +1,11 @@
 use super::{get_sign, parser, OkxCredential};
 use crate::prelude::*;
+use crate::utils::alert::{AlertLevel, AlertManager};
 use anyhow::anyhow;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
 
 #[cfg(feature = "testnet")]
 const REST_URL: &str = "https://www.okx.com";
@@ -10,71 +13,161 @@ const REST_URL: &str = "https://www.okx.com";
 #[cfg(not(feature = "testnet"))]
 const REST_URL: &str = "https://www.okx.com";
 
+/// OKX's cap on the number of orders accepted in one
+/// `batch-orders`/`cancel-batch-orders` request.
+const REST_BATCH_MAX: usize = 20;
+
 pub(super) struct RestClient {
     apikey: String,
     secretkey: String,
     passphrase: String,
     client: reqwest::Client,
+    /// `cl_ord_id`s placed through [`Self::send_algo_order`], so a later
+    /// [`Msg::CancelOrder`] for the same id knows to cancel it via
+    /// `/api/v5/trade/cancel-algos` instead of the plain cancel path.
+    algo_cl_ord_ids: HashSet<i64>,
+    /// notified, alongside the usual `log::error!`, whenever an order or
+    /// cancel is rejected. `None` if the caller didn't wire one up.
+    alert_manager: Option<Arc<AlertManager>>,
 }
 
 impl RestClient {
-    pub fn new(credential: &OkxCredential) -> Self {
+    pub fn new(credential: &OkxCredential, alert_manager: Option<Arc<AlertManager>>) -> Self {
         Self {
             apikey: credential.apikey.to_owned(),
             secretkey: credential.secretkey.to_owned(),
             passphrase: credential.passphrase.to_owned(),
             client: reqwest::Client::new(),
+            algo_cl_ord_ids: HashSet::new(),
+            alert_manager,
+        }
+    }
+
+    async fn alert(&self, msg: String) {
+        if let Some(alert_manager) = &self.alert_manager {
+            alert_manager.alert(AlertLevel::Warning, &msg).await;
         }
     }
 
-    pub async fn run(self, tx: MsgSender, mut rx: MsgReceiver) {
+    pub async fn run(mut self, tx: MsgSender, mut rx: MsgReceiver) {
         while let Some(msg) = rx.recv().await {
             match msg {
                 Msg::NewOrder(req) => {
                     log::info!("send order {req:?}");
-                    if let Err(msg) = self.send_order(&req).await {
+                    let is_algo = matches!(
+                        req.ord_type,
+                        OrdType::TriggerLimit { .. }
+                            | OrdType::TriggerMarket { .. }
+                            | OrdType::TrailingStop { .. }
+                    );
+                    let result = if is_algo {
+                        self.send_algo_order(&req).await
+                    } else {
+                        self.send_order(&req).await
+                    };
+                    if is_algo && result.is_ok() {
+                        self.algo_cl_ord_ids.insert(req.cl_ord_id);
+                    }
+                    if let Err(msg) = result {
                         log::error!("send order error {}", msg);
-                        let ts = chrono::Utc::now().naive_utc();
-                        let er = ExecutionReport {
-                            c_time: ts,
-                            u_time: ts,
-                            inst: req.inst,
-                            ccy: Ccy::default(),
-                            ord_id: 0,
-                            cl_ord_id: req.cl_ord_id,
-                            px: req.px,
-                            sz: req.sz,
-                            notional_usd: 0.0,
-                            ord_type: req.ord_type,
-                            side: req.side,
-                            fill_px: 0.0,
-                            fill_sz: 0.0,
-                            acc_fill_sz: 0.0,
-                            avg_px: 0.0,
-                            state: OrdState::Rejected,
-                            lever: 0.0,
-                            fee: 0.0,
-                        };
-                        tx.send(Msg::ExecutionReport(er)).await.unwrap();
+                        self.alert(format!("order {} rejected: {}", req.cl_ord_id, msg))
+                            .await;
+                        tx.send(Msg::ExecutionReport(rejected_execution_report(&req)))
+                            .await
+                            .unwrap();
                     }
                 }
                 Msg::CancelOrder(req) => {
                     log::info!("cancel order {req:?}");
-                    if let Err(msg) = self.cancel_order(&req).await {
+                    let result = if self.algo_cl_ord_ids.remove(&req.cl_ord_id) {
+                        self.cancel_algo_order(&req).await
+                    } else {
+                        self.cancel_order(&req).await
+                    };
+                    if let Err(msg) = result {
                         log::error!("cancel order error {}", msg);
-                        let cj = CancelReject {
-                            u_time: chrono::Utc::now().naive_utc(),
-                            inst: req.inst,
-                            cl_ord_id: req.cl_ord_id,
-                        };
-                        tx.send(Msg::CancelReject(cj)).await.unwrap();
+                        self.alert(format!("cancel {} rejected: {}", req.cl_ord_id, msg))
+                            .await;
+                        tx.send(Msg::CancelReject(cancel_reject(&req)))
+                            .await
+                            .unwrap();
+                    }
+                }
+                Msg::NewOrderBatch(orders) => {
+                    log::info!("send order batch of {} orders", orders.len());
+                    for chunk in orders.chunks(REST_BATCH_MAX) {
+                        match self.send_orders(chunk).await {
+                            Ok(data) => {
+                                for (no, item) in chunk.iter().zip(data.iter()) {
+                                    if item["sCode"].as_str().unwrap_or_default() != "0" {
+                                        let reason = item["sMsg"].as_str().unwrap_or_default();
+                                        log::error!("order {} rejected: {}", no.cl_ord_id, reason);
+                                        self.alert(format!(
+                                            "order {} rejected: {}",
+                                            no.cl_ord_id, reason
+                                        ))
+                                        .await;
+                                        tx.send(Msg::ExecutionReport(rejected_execution_report(
+                                            no,
+                                        )))
+                                        .await
+                                        .unwrap();
+                                    }
+                                }
+                            }
+                            Err(msg) => {
+                                log::error!("send order batch error {}", msg);
+                                self.alert(format!("order batch rejected: {}", msg)).await;
+                                for no in chunk {
+                                    tx.send(Msg::ExecutionReport(rejected_execution_report(no)))
+                                        .await
+                                        .unwrap();
+                                }
+                            }
+                        }
+                    }
+                }
+                Msg::CancelOrderBatch(orders) => {
+                    log::info!("cancel order batch of {} orders", orders.len());
+                    for chunk in orders.chunks(REST_BATCH_MAX) {
+                        match self.cancel_orders(chunk).await {
+                            Ok(data) => {
+                                for (co, item) in chunk.iter().zip(data.iter()) {
+                                    if item["sCode"].as_str().unwrap_or_default() != "0" {
+                                        let reason = item["sMsg"].as_str().unwrap_or_default();
+                                        log::error!(
+                                            "cancel {} rejected: {}",
+                                            co.cl_ord_id,
+                                            reason
+                                        );
+                                        self.alert(format!(
+                                            "cancel {} rejected: {}",
+                                            co.cl_ord_id, reason
+                                        ))
+                                        .await;
+                                        tx.send(Msg::CancelReject(cancel_reject(co)))
+                                            .await
+                                            .unwrap();
+                                    }
+                                }
+                            }
+                            Err(msg) => {
+                                log::error!("cancel order batch error {}", msg);
+                                self.alert(format!("cancel batch rejected: {}", msg)).await;
+                                for co in chunk {
+                                    tx.send(Msg::CancelReject(cancel_reject(co)))
+                                        .await
+                                        .unwrap();
+                                }
+                            }
+                        }
                     }
                 }
                 _ => (),
             }
         }
     }
-    
+
     fn get_headers(&self, path: &str, body: &str) -> Result<HeaderMap> {
         let ts = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
         let sign = get_sign(&ts, "POST", path, body, &self.secretkey);
@@ -92,7 +185,12 @@ impl RestClient {
         Ok(headers)
     }
 
-    async fn post(&self, path: &str, body: String) -> Result<()> {
+    /// posts `body` to `path` and returns the response's `data` array
+    /// as-is, one entry per item in the request (a single-object request
+    /// still comes back as a one-element array). Only the top-level
+    /// `code`/`msg` are checked here; per-item `sCode`/`sMsg` are the
+    /// caller's concern, since a batch request can partially fail.
+    async fn post(&self, path: &str, body: String) -> Result<Vec<Value>> {
         let headers = self.get_headers(path, &body)?;
         let resp = self
             .client
@@ -109,11 +207,17 @@ impl RestClient {
             let msg = v["msg"].as_str().unwrap();
             return Err(anyhow!(msg.to_owned()));
         }
-        let s_code = v["data"][0]["sCode"].as_str().unwrap();
+        Ok(v["data"].as_array().cloned().unwrap_or_default())
+    }
+
+    /// checks the single-item `sCode`/`sMsg` pair returned by a
+    /// non-batch request.
+    fn check_single(data: &[Value]) -> Result<()> {
+        let s_code = data[0]["sCode"].as_str().unwrap();
         if s_code == "0" {
             Ok(())
         } else {
-            let s_msg = v["data"][0]["sMsg"].as_str().unwrap();
+            let s_msg = data[0]["sMsg"].as_str().unwrap();
             Err(anyhow!(s_msg.to_owned()))
         }
     }
@@ -121,14 +225,14 @@ impl RestClient {
     async fn send_order(&self, no: &NewOrder) -> Result<()> {
         let req = json!({
             "instId": parser::inst_to_str(&no.inst),
-            "tdMode": parser::td_mod_to_str(&no.td_mod),
+            "tdMode": parser::td_mode_to_str(&no.td_mode),
             "side": parser::side_to_str(&no.side),
             "ordType": parser::ord_type_to_str(&no.ord_type),
             "px": no.px.to_string(),
             "sz": no.sz.to_string(),
         });
         let body = serde_json::to_string(&req)?;
-        self.post("/api/v5/trade/order", body).await
+        Self::check_single(&self.post("/api/v5/trade/order", body).await?)
     }
 
     async fn cancel_order(&self, co: &CancelOrder) -> Result<()> {
@@ -137,6 +241,185 @@ impl RestClient {
             "instId": parser::inst_to_str(&co.inst),
         });
         let body = serde_json::to_string(&req)?;
-        self.post("/api/v5/trade/order", body).await
+        Self::check_single(&self.post("/api/v5/trade/order", body).await?)
+    }
+
+    /// places a [`OrdType::TriggerLimit`]/[`OrdType::TriggerMarket`]/
+    /// [`OrdType::TrailingStop`] order over `/api/v5/trade/order-algo`,
+    /// OKX's dedicated endpoint for conditional/trigger/trailing orders.
+    async fn send_algo_order(&self, no: &NewOrder) -> Result<()> {
+        let body = serde_json::to_string(&algo_order_req(no))?;
+        Self::check_single(&self.post("/api/v5/trade/order-algo", body).await?)
+    }
+
+    /// cancels an order placed through [`Self::send_algo_order`] over
+    /// `/api/v5/trade/cancel-algos`, OKX's algo-order counterpart to
+    /// `/api/v5/trade/cancel-order`.
+    async fn cancel_algo_order(&self, co: &CancelOrder) -> Result<()> {
+        let req = json!([{
+            "algoClOrdId": co.cl_ord_id.to_string(),
+            "instId": parser::inst_to_str(&co.inst),
+        }]);
+        let body = serde_json::to_string(&req)?;
+        Self::check_single(&self.post("/api/v5/trade/cancel-algos", body).await?)
+    }
+
+    /// places up to [`REST_BATCH_MAX`] orders in one
+    /// `/api/v5/trade/batch-orders` request. The response `data` array
+    /// is positional, one entry per item in `orders`, so the caller can
+    /// zip it back against `orders` to find which ones were rejected.
+    async fn send_orders(&self, orders: &[NewOrder]) -> Result<Vec<Value>> {
+        let req: Vec<Value> = orders
+            .iter()
+            .map(|no| {
+                json!({
+                    "instId": parser::inst_to_str(&no.inst),
+                    "tdMode": parser::td_mode_to_str(&no.td_mode),
+                    "clOrdId": no.cl_ord_id.to_string(),
+                    "side": parser::side_to_str(&no.side),
+                    "ordType": parser::ord_type_to_str(&no.ord_type),
+                    "px": no.px.to_string(),
+                    "sz": no.sz.to_string(),
+                })
+            })
+            .collect();
+        let body = serde_json::to_string(&req)?;
+        self.post("/api/v5/trade/batch-orders", body).await
+    }
+
+    /// cancels up to [`REST_BATCH_MAX`] orders in one
+    /// `/api/v5/trade/cancel-batch-orders` request. See [`Self::send_orders`]
+    /// for the positional response shape.
+    async fn cancel_orders(&self, orders: &[CancelOrder]) -> Result<Vec<Value>> {
+        let req: Vec<Value> = orders
+            .iter()
+            .map(|co| {
+                json!({
+                    "clOrdId": co.cl_ord_id.to_string(),
+                    "instId": parser::inst_to_str(&co.inst),
+                })
+            })
+            .collect();
+        let body = serde_json::to_string(&req)?;
+        self.post("/api/v5/trade/cancel-batch-orders", body).await
+    }
+}
+
+/// builds the `/api/v5/trade/order-algo` request body for a
+/// [`OrdType::TriggerLimit`]/[`OrdType::TriggerMarket`]/[`OrdType::TrailingStop`]
+/// order. Split out of [`RestClient::send_algo_order`] so the per-variant
+/// field mapping can be tested without a live connection.
+fn algo_order_req(no: &NewOrder) -> Value {
+    let mut req = json!({
+        "instId": parser::inst_to_str(&no.inst),
+        "tdMode": parser::td_mode_to_str(&no.td_mode),
+        "side": parser::side_to_str(&no.side),
+        "ordType": parser::ord_type_to_str(&no.ord_type),
+        "sz": no.sz.to_string(),
+        "algoClOrdId": no.cl_ord_id.to_string(),
+    });
+    match no.ord_type {
+        OrdType::TriggerLimit { trigger_px, ord_px } => {
+            req["triggerPx"] = trigger_px.to_string().into();
+            req["orderPx"] = ord_px.to_string().into();
+        }
+        OrdType::TriggerMarket { trigger_px } => {
+            req["triggerPx"] = trigger_px.to_string().into();
+            req["orderPx"] = "-1".into();
+        }
+        OrdType::TrailingStop {
+            callback_ratio,
+            callback_spread,
+        } => {
+            if let Some(callback_ratio) = callback_ratio {
+                req["callbackRatio"] = callback_ratio.to_string().into();
+            }
+            if let Some(callback_spread) = callback_spread {
+                req["callbackSpread"] = callback_spread.to_string().into();
+            }
+        }
+        _ => unreachable!("algo_order_req called with a non-algo OrdType"),
+    }
+    req
+}
+
+fn rejected_execution_report(req: &NewOrder) -> ExecutionReport {
+    let ts = chrono::Utc::now().naive_utc();
+    ExecutionReport {
+        c_time: ts,
+        u_time: ts,
+        inst: req.inst.clone(),
+        ord_id: 0,
+        cl_ord_id: req.cl_ord_id,
+        px: req.px,
+        sz: req.sz,
+        notional_usd: 0.0,
+        ord_type: req.ord_type,
+        side: req.side,
+        fill_px: 0.0,
+        fill_sz: 0.0,
+        acc_fill_sz: 0.0,
+        avg_px: 0.0,
+        state: OrdState::Rejected,
+        lever: 0.0,
+        fee: 0.0,
+    }
+}
+
+fn cancel_reject(req: &CancelOrder) -> CancelReject {
+    CancelReject {
+        u_time: chrono::Utc::now().naive_utc(),
+        inst: req.inst.clone(),
+        cl_ord_id: req.cl_ord_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_algo_order(ord_type: OrdType) -> NewOrder {
+        NewOrder {
+            inst: Inst::try_from("Okx.BTC.USDT.Swap").unwrap(),
+            cl_ord_id: 1,
+            side: Side::Buy,
+            ord_type,
+            td_mode: TdMode::Cross,
+            px: 0.0,
+            sz: 1.0,
+        }
+    }
+
+    #[test]
+    fn algo_order_req_maps_trigger_limit() {
+        let no = new_algo_order(OrdType::TriggerLimit {
+            trigger_px: 100.0,
+            ord_px: 99.0,
+        });
+        let req = algo_order_req(&no);
+        assert_eq!(req["triggerPx"], "100");
+        assert_eq!(req["orderPx"], "99");
+        assert_eq!(req["ordType"], "conditional");
+    }
+
+    #[test]
+    fn algo_order_req_maps_trigger_market() {
+        let no = new_algo_order(OrdType::TriggerMarket { trigger_px: 100.0 });
+        let req = algo_order_req(&no);
+        assert_eq!(req["triggerPx"], "100");
+        assert_eq!(req["orderPx"], "-1");
+        assert_eq!(req["ordType"], "trigger");
+    }
+
+    #[test]
+    fn algo_order_req_maps_trailing_stop() {
+        let no = new_algo_order(OrdType::TrailingStop {
+            callback_ratio: Some(0.05),
+            callback_spread: None,
+        });
+        let req = algo_order_req(&no);
+        assert_eq!(req["callbackRatio"], "0.05");
+        assert!(req.get("callbackSpread").is_none());
+        assert_eq!(req["ordType"], "move_order_stop");
     }
 }
@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serde_with::{serde_as, DisplayFromStr};
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, watch};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 #[cfg(feature = "testnet")]
@@ -40,10 +43,11 @@ pub enum WsChannel {
     FundingRate,
     OpenInterest,
     Books5,
+    Books,
     Trade,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WsChannelArg {
     pub channel: WsChannel,
@@ -81,19 +85,228 @@ struct WsCancel {
     cl_ord_id: String,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WsAmend {
+    inst_id: String,
+    cl_ord_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_sz: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    new_px: Option<f64>,
+}
+
 #[derive(Serialize)]
 #[serde(tag = "op", rename_all = "snake_case")]
 enum WsCommand {
     Login { args: Vec<WsAccount> },
     Subscribe { args: Vec<WsChannelArg> },
+    Unsubscribe { args: Vec<WsChannelArg> },
     Order { id: String, args: Vec<WsOrder> },
     CancelOrder { id: String, args: Vec<WsCancel> },
+    AmendOrder { id: String, args: Vec<WsAmend> },
+    BatchOrders { id: String, args: Vec<WsOrder> },
+    BatchCancelOrders { id: String, args: Vec<WsCancel> },
+}
+
+/// a runtime subscription change, translated by [`PublicClient`] into a
+/// single incremental `subscribe`/`unsubscribe` frame (covering every
+/// `(Inst, DataType)` pair at once) on the live connection.
+#[derive(Debug, Clone)]
+pub enum SubscriptionCommand {
+    Subscribe(Vec<(Inst, DataType)>),
+    Unsubscribe(Vec<(Inst, DataType)>),
+}
+
+/// a trading request submitted through [`super::OkxTrader`]. New and amend
+/// orders carry a oneshot that [`PrivateClient`] fulfils with the order's
+/// execution report once OKX acknowledges (or rejects) it, so a caller can
+/// await a specific order rather than eavesdrop on the whole [`MsgReceiver`].
+pub enum OrderCommand {
+    NewOrder(NewOrder, oneshot::Sender<ExecutionReport>),
+    CancelOrder(CancelOrder),
+    AmendOrder(AmendOrder, oneshot::Sender<ExecutionReport>),
+}
+
+fn build_ws_order(o: &NewOrder) -> WsOrder {
+    WsOrder {
+        inst_id: parser::inst_to_str(&o.inst),
+        td_mode: parser::td_mode_to_str(&o.td_mode).to_owned(),
+        cl_ord_id: o.cl_ord_id.to_string(),
+        side: parser::side_to_str(&o.side).to_owned(),
+        ord_type: parser::ord_type_to_str(&o.ord_type).to_owned(),
+        sz: o.sz,
+        px: o.px,
+    }
+}
+
+fn build_ws_amend(a: &AmendOrder) -> WsAmend {
+    WsAmend {
+        inst_id: parser::inst_to_str(&a.inst),
+        cl_ord_id: a.cl_ord_id.to_string(),
+        new_sz: a.new_sz,
+        new_px: a.new_px,
+    }
+}
+
+pub(crate) fn channel_for_data_type(data_type: DataType) -> WsChannel {
+    match data_type {
+        DataType::Depth => WsChannel::Books5,
+        DataType::Book => WsChannel::Books,
+        DataType::Trade => WsChannel::Trade,
+        DataType::Ticker => WsChannel::Tickers,
+        DataType::OpenInterest => WsChannel::OpenInterest,
+        DataType::FundingRate => WsChannel::FundingRate,
+    }
+}
+
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// OKX drops an idle socket after ~30s of silence, so the default ping
+/// cadence stays comfortably under that.
+pub(crate) const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(20);
+pub(crate) const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(40);
+
+/// how long [`PrivateClient::run_connection`] waits for more new/cancel
+/// orders to arrive before flushing whatever it has into a single
+/// `batch-orders`/`batch-cancel-orders` frame.
+const ORDER_BATCH_WINDOW: Duration = Duration::from_millis(20);
+/// OKX's cap on the number of orders accepted in one batch frame.
+const ORDER_BATCH_MAX: usize = 20;
+
+/// adds up to 250ms of jitter to a reconnect backoff so a batch of clients
+/// that dropped together don't all hammer OKX at the same instant.
+fn with_jitter(backoff: Duration) -> Duration {
+    let jitter_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_millis() % 250)
+        .unwrap_or(0);
+    backoff + Duration::from_millis(jitter_ms as u64)
+}
+
+fn channel_arg_for(inst: &Inst, data_type: DataType) -> WsChannelArg {
+    WsChannelArg {
+        channel: channel_for_data_type(data_type),
+        inst_id: Some(parser::inst_to_str(inst)),
+        inst_type: None,
+        inst_family: None,
+    }
+}
+
+/// a full local order book for one instrument, maintained from OKX's
+/// `books` channel snapshot + incremental updates. Raw price/size strings
+/// are kept alongside the parsed `f64`s because [`OrderBook::checksum`]
+/// must hash OKX's exact field text, not a reformatted float.
+#[derive(Debug, Default)]
+struct OrderBook {
+    /// (price, raw price, size, raw size), sorted descending by price
+    bids: Vec<(f64, String, f64, String)>,
+    /// (price, raw price, size, raw size), sorted ascending by price
+    asks: Vec<(f64, String, f64, String)>,
+}
+
+fn parse_book_levels(levels: &Value) -> Result<Vec<(f64, String, f64, String)>> {
+    levels
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|l| {
+            let px_str = l[0]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("missing or non-string price in level {l}"))?
+                .to_owned();
+            let sz_str = l[1]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("missing or non-string size in level {l}"))?
+                .to_owned();
+            let px = px_str
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid price in level {l}: {e}"))?;
+            let sz = sz_str
+                .parse()
+                .map_err(|e| anyhow::anyhow!("invalid size in level {l}: {e}"))?;
+            Ok((px, px_str, sz, sz_str))
+        })
+        .collect()
+}
+
+impl OrderBook {
+    fn apply_snapshot(&mut self, d: &Value) -> Result<()> {
+        self.bids = parse_book_levels(&d["bids"])?;
+        self.bids.sort_by(|a, b| b.0.total_cmp(&a.0));
+        self.asks = parse_book_levels(&d["asks"])?;
+        self.asks.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Ok(())
+    }
+
+    fn apply_update(&mut self, d: &Value) -> Result<()> {
+        for (px, px_str, sz, sz_str) in parse_book_levels(&d["bids"])? {
+            Self::apply_level(&mut self.bids, px, px_str, sz, sz_str, false);
+        }
+        for (px, px_str, sz, sz_str) in parse_book_levels(&d["asks"])? {
+            Self::apply_level(&mut self.asks, px, px_str, sz, sz_str, true);
+        }
+        Ok(())
+    }
+
+    /// a size of `0` removes the level; otherwise inserts/overwrites it.
+    fn apply_level(
+        levels: &mut Vec<(f64, String, f64, String)>,
+        px: f64,
+        px_str: String,
+        sz: f64,
+        sz_str: String,
+        ascending: bool,
+    ) {
+        let pos = levels.partition_point(|(p, ..)| if ascending { *p < px } else { *p > px });
+        let is_removal = sz == 0.0;
+        if levels.get(pos).is_some_and(|(p, ..)| *p == px) {
+            if is_removal {
+                levels.remove(pos);
+            } else {
+                levels[pos] = (px, px_str, sz, sz_str);
+            }
+        } else if !is_removal {
+            levels.insert(pos, (px, px_str, sz, sz_str));
+        }
+    }
+
+    /// OKX's book checksum: interleave `bidPx:bidSz:askPx:askSz` for as many
+    /// of the first 25 levels as both sides have, join with `:`, CRC32
+    /// (IEEE) the UTF-8 bytes, and reinterpret the unsigned result as `i32`.
+    fn checksum(&self) -> i32 {
+        let depth = self.bids.len().min(self.asks.len()).min(25);
+        let mut parts = Vec::with_capacity(depth * 4);
+        for i in 0..depth {
+            parts.push(self.bids[i].1.as_str());
+            parts.push(self.bids[i].3.as_str());
+            parts.push(self.asks[i].1.as_str());
+            parts.push(self.asks[i].3.as_str());
+        }
+        crc32fast::hash(parts.join(":").as_bytes()) as i32
+    }
+
+    fn to_msg(&self, inst: Inst, exch_time: chrono::NaiveDateTime) -> Book {
+        Book {
+            inst,
+            exch_time,
+            recv_time: Utc::now().naive_utc(),
+            bids: self.bids.iter().map(|(px, _, sz, _)| (*px, *sz)).collect(),
+            asks: self.asks.iter().map(|(px, _, sz, _)| (*px, *sz)).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 enum WsMessage {
-    Data { data: Vec<Value>, arg: WsChannelArg },
+    Data {
+        data: Vec<Value>,
+        arg: WsChannelArg,
+        /// `snapshot` or `update`, present on `books`/`books5` pushes.
+        action: Option<String>,
+    },
     TradeResult(TradeResult),
     LoginResult(LoginResult),
     SubscribeResult { arg: WsChannelArg },
@@ -104,6 +317,9 @@ enum WsMessage {
 enum WsOp {
     Order,
     CancelOrder,
+    AmendOrder,
+    BatchOrders,
+    BatchCancelOrders,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -119,6 +335,8 @@ struct TradeResult {
     #[serde_as(as = "DisplayFromStr")]
     id: i64,
     op: WsOp,
+    #[serde(default)]
+    data: Vec<TradeResultItem>,
     #[serde_as(as = "DisplayFromStr")]
     code: i64,
     msg: String,
@@ -130,6 +348,29 @@ impl TradeResult {
     }
 }
 
+/// one order's outcome within a [`TradeResult`]. `s_code`/`s_msg` are this
+/// item's own success code/message, which for a `batch-orders`/
+/// `batch-cancel-orders` frame can differ per item even though `code` on
+/// the enclosing [`TradeResult`] is `"0"`. Left as raw strings (rather than
+/// parsed with `DisplayFromStr` like [`TradeResult::code`]) since OKX's
+/// `clOrdId` is blank when a cancel targets an order by `ordId` instead.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TradeResultItem {
+    #[serde(default)]
+    cl_ord_id: String,
+    #[serde(default)]
+    s_code: String,
+    #[serde(default)]
+    s_msg: String,
+}
+
+impl TradeResultItem {
+    fn is_ok(&self) -> bool {
+        self.s_code == "0"
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize)]
 struct LoginResult {
@@ -147,67 +388,246 @@ impl LoginResult {
 
 pub struct PublicClient {
     pub channels: Vec<WsChannelArg>,
+    pub sub_rx: Option<mpsc::Receiver<SubscriptionCommand>>,
+    /// keepalive ping cadence. see [`super::WsClientBuilder::heartbeat`].
+    pub heartbeat_interval: Duration,
+    /// how long to wait for a `pong` before reconnecting.
+    pub heartbeat_timeout: Duration,
 }
 
 impl PublicClient {
-    pub async fn start(self, tx: MsgSender) -> Result<()> {
-        let (mut ws, _) = connect_async(PUBLIC_WS_URL).await.unwrap();
+    /// runs until `shutdown_rx` reports a shutdown, reconnecting with
+    /// exponential backoff (capped, with jitter) whenever the socket drops
+    /// in the meantime. The set of subscribed channels is treated as durable
+    /// state: it starts from `self.channels`, evolves as
+    /// [`SubscriptionCommand`]s arrive, and is replayed in full on every
+    /// reconnect instead of only the original construction-time set.
+    pub async fn start(mut self, tx: MsgSender, mut shutdown_rx: watch::Receiver<bool>) -> Result<()> {
+        let mut active = self.channels;
+        let mut sub_rx = self.sub_rx.take();
+        let heartbeat_interval = self.heartbeat_interval;
+        let heartbeat_timeout = self.heartbeat_timeout;
+        let mut backoff = RECONNECT_INITIAL_BACKOFF;
+        loop {
+            tx.send(Msg::ConnectionState(ConnectionState::Connecting))
+                .await?;
+            let result = Self::run_connection(
+                &mut active,
+                &mut sub_rx,
+                &tx,
+                &mut backoff,
+                heartbeat_interval,
+                heartbeat_timeout,
+                &mut shutdown_rx,
+            )
+            .await;
+            if *shutdown_rx.borrow() {
+                return Ok(());
+            }
+            match result {
+                Ok(()) => log::warn!("okx public websocket disconnected, reconnecting"),
+                Err(e) => log::warn!("okx public websocket error: {e}, reconnecting"),
+            }
+            tx.send(Msg::ConnectionState(ConnectionState::Disconnected))
+                .await?;
+            let wait = with_jitter(backoff);
+            log::info!("reconnecting to okx public websocket in {:?}", wait);
+            tx.send(Msg::ConnectionState(ConnectionState::Reconnecting))
+                .await?;
+            tokio::time::sleep(wait).await;
+            backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_connection(
+        active: &mut Vec<WsChannelArg>,
+        sub_rx: &mut Option<mpsc::Receiver<SubscriptionCommand>>,
+        tx: &MsgSender,
+        backoff: &mut Duration,
+        heartbeat_interval: Duration,
+        heartbeat_timeout: Duration,
+        shutdown_rx: &mut watch::Receiver<bool>,
+    ) -> Result<()> {
+        let (mut ws, _) = connect_async(PUBLIC_WS_URL).await?;
         log::info!("connected to okx public websocket");
         let cmd = WsCommand::Subscribe {
-            args: self.channels,
+            args: active.clone(),
         };
         ws.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
-        log::info!("send subscribe request");
-        while let Some(msg) = ws.next().await {
-            if let Message::Text(payload) = msg? {
-                let ws_msg: WsMessage = serde_json::from_str(&payload)?;
-                match ws_msg {
-                    WsMessage::Data { data, arg } => match arg.channel {
-                        WsChannel::Tickers => {
-                            for d in data {
-                                if let Ok(m) = parser::parse_ticker(&d) {
-                                    tx.send(Msg::Ticker(m)).await?;
-                                }
-                            }
+        log::info!("sent subscribe request for {} channel(s)", active.len());
+        tx.send(Msg::ConnectionState(ConnectionState::Connected))
+            .await?;
+        if active.is_empty() {
+            // nothing to subscribe to, so no SubscribeResult will ever
+            // arrive to reset the backoff below; the connection itself
+            // succeeding is the only confirmation we'll get.
+            *backoff = RECONNECT_INITIAL_BACKOFF;
+        }
+        let mut books: HashMap<String, OrderBook> = HashMap::new();
+        let mut hb_tick = tokio::time::interval(heartbeat_interval);
+        hb_tick.tick().await;
+        let mut last_pong = tokio::time::Instant::now();
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow() {
+                        let _ = ws.send(Message::Close(None)).await;
+                        return Ok(());
+                    }
+                }
+                _ = hb_tick.tick() => {
+                    if last_pong.elapsed() > heartbeat_timeout {
+                        return Err(anyhow::anyhow!(
+                            "no pong received within {:?}",
+                            heartbeat_timeout
+                        ));
+                    }
+                    ws.send(Message::Text("ping".to_string())).await?;
+                }
+                cmd = recv_sub(sub_rx) => {
+                    let Some(cmd) = cmd else { continue };
+                    let ws_cmd = match &cmd {
+                        SubscriptionCommand::Subscribe(subs) => {
+                            log::info!("runtime subscribe: {} channel(s)", subs.len());
+                            let args: Vec<WsChannelArg> = subs
+                                .iter()
+                                .map(|(inst, data_type)| channel_arg_for(inst, *data_type))
+                                .collect();
+                            active.extend(args.iter().cloned());
+                            WsCommand::Subscribe { args }
                         }
-                        WsChannel::FundingRate => {
-                            for d in data {
-                                if let Ok(m) = parser::parse_funding_rate(&d) {
-                                    tx.send(Msg::FundingRate(m)).await?;
-                                }
-                            }
+                        SubscriptionCommand::Unsubscribe(subs) => {
+                            log::info!("runtime unsubscribe: {} channel(s)", subs.len());
+                            let args: Vec<WsChannelArg> = subs
+                                .iter()
+                                .map(|(inst, data_type)| channel_arg_for(inst, *data_type))
+                                .collect();
+                            active.retain(|a| !args.contains(a));
+                            WsCommand::Unsubscribe { args }
                         }
-                        WsChannel::OpenInterest => {
-                            for d in data {
-                                if let Ok(m) = parser::parse_open_interest(&d) {
-                                    tx.send(Msg::OpenInterest(m)).await?;
-                                }
-                            }
+                    };
+                    ws.send(Message::Text(serde_json::to_string(&ws_cmd)?)).await?;
+                }
+                msg = ws.next() => {
+                    let Some(msg) = msg else { return Ok(()) };
+                    if let Message::Text(payload) = msg? {
+                        if payload == "pong" {
+                            last_pong = tokio::time::Instant::now();
+                            continue;
                         }
-                        WsChannel::Books5 => {
-                            for d in data {
-                                if let Ok(m) = parser::parse_books5(&d) {
-                                    tx.send(Msg::Depth(m)).await?;
+                        let ws_msg: WsMessage = serde_json::from_str(&payload)?;
+                        match ws_msg {
+                            WsMessage::Data { data, arg, action } => match arg.channel {
+                                WsChannel::Books => {
+                                    let inst_id = arg.inst_id.clone().unwrap_or_default();
+                                    for d in data {
+                                        let book = books.entry(inst_id.clone()).or_default();
+                                        let result = if action.as_deref() == Some("snapshot") {
+                                            book.apply_snapshot(&d)
+                                        } else {
+                                            book.apply_update(&d)
+                                        };
+                                        if let Err(e) = result {
+                                            log::error!("dropping malformed book update: {e}");
+                                            books.remove(&inst_id);
+                                            continue;
+                                        }
+                                        let book = &books[&inst_id];
+                                        let reported_checksum = d["checksum"].as_i64();
+                                        if reported_checksum.is_some_and(|c| c != book.checksum() as i64) {
+                                            log::warn!(
+                                                "okx order book checksum mismatch for {}, resyncing",
+                                                inst_id
+                                            );
+                                            books.remove(&inst_id);
+                                            let resync = arg.clone();
+                                            let resub = WsCommand::Unsubscribe { args: vec![resync.clone()] };
+                                            ws.send(Message::Text(serde_json::to_string(&resub)?)).await?;
+                                            let resub = WsCommand::Subscribe { args: vec![resync] };
+                                            ws.send(Message::Text(serde_json::to_string(&resub)?)).await?;
+                                            continue;
+                                        }
+                                        let inst = match parser::str_to_inst(&inst_id) {
+                                            Ok(inst) => inst,
+                                            Err(e) => {
+                                                log::error!("dropping malformed book update: {e}");
+                                                continue;
+                                            }
+                                        };
+                                        let exch_time = match parser::str_to_naive_datetime(
+                                            d["ts"].as_str().unwrap_or_default(),
+                                        ) {
+                                            Ok(t) => t,
+                                            Err(e) => {
+                                                log::error!("dropping malformed book update: {e}");
+                                                continue;
+                                            }
+                                        };
+                                        tx.send(Msg::Book(book.to_msg(inst, exch_time))).await?;
+                                    }
                                 }
-                            }
-                        }
-                        WsChannel::Trade => {
-                            for d in data {
-                                if let Ok(m) = parser::parse_trade(&d) {
-                                    tx.send(Msg::Trade(m)).await?;
+                                WsChannel::Tickers => {
+                                    for d in data {
+                                        match parser::parse_ticker(&d) {
+                                            Ok(m) => tx.send(Msg::Ticker(m)).await?,
+                                            Err(e) => log::error!("dropping malformed ticker: {e}"),
+                                        }
+                                    }
+                                }
+                                WsChannel::FundingRate => {
+                                    for d in data {
+                                        match parser::parse_funding_rate(&d) {
+                                            Ok(m) => tx.send(Msg::FundingRate(m)).await?,
+                                            Err(e) => log::error!("dropping malformed funding rate: {e}"),
+                                        }
+                                    }
+                                }
+                                WsChannel::OpenInterest => {
+                                    for d in data {
+                                        match parser::parse_open_interest(&d) {
+                                            Ok(m) => tx.send(Msg::OpenInterest(m)).await?,
+                                            Err(e) => log::error!("dropping malformed open interest: {e}"),
+                                        }
+                                    }
                                 }
+                                WsChannel::Books5 => {
+                                    for d in data {
+                                        match parser::parse_books5(&d) {
+                                            Ok(m) => tx.send(Msg::Depth(m)).await?,
+                                            Err(e) => log::error!("dropping malformed books5 update: {e}"),
+                                        }
+                                    }
+                                }
+                                WsChannel::Trade => {
+                                    for d in data {
+                                        match parser::parse_trade(&d) {
+                                            Ok(m) => tx.send(Msg::Trade(m)).await?,
+                                            Err(e) => log::error!("dropping malformed trade: {e}"),
+                                        }
+                                    }
+                                }
+                                _ => (),
+                            },
+                            WsMessage::SubscribeResult { arg } => {
+                                log::info!("subscribe succeed. {:?} {:?}", arg.inst_id, arg.channel);
+                                *backoff = RECONNECT_INITIAL_BACKOFF;
                             }
+                            _ => log::error!("unexpected message: {:?}", ws_msg),
                         }
-                        _ => (),
-                    },
-                    WsMessage::SubscribeResult { arg } => {
-                        log::info!("subscribe succeed. {:?} {:?}", arg.inst_id, arg.channel);
                     }
-                    _ => log::error!("unexpected message: {:?}", ws_msg),
                 }
             }
         }
-        Ok(())
+    }
+}
+
+async fn recv_sub(
+    sub_rx: &mut Option<mpsc::Receiver<SubscriptionCommand>>,
+) -> Option<SubscriptionCommand> {
+    match sub_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
     }
 }
 
@@ -216,19 +636,120 @@ pub struct PrivateClient {
     pub secretkey: String,
     pub passphrase: String,
     pub channels: Vec<WsChannelArg>,
+    /// trading requests submitted through a [`super::OkxTrader`] handle.
+    pub order_rx: Option<mpsc::Receiver<OrderCommand>>,
+    /// acks awaited by a [`super::OkxTrader`], keyed by `cl_ord_id`. Fulfilled
+    /// (and removed) as soon as the matching execution report or order
+    /// reject arrives, in addition to being forwarded on `tx` as usual.
+    pub pending_acks: Arc<Mutex<HashMap<i64, oneshot::Sender<ExecutionReport>>>>,
+    /// keepalive ping cadence. see [`super::WsClientBuilder::heartbeat`].
+    pub heartbeat_interval: Duration,
+    /// how long to wait for a `pong` before reconnecting.
+    pub heartbeat_timeout: Duration,
+}
+
+async fn recv_order(order_rx: &mut Option<mpsc::Receiver<OrderCommand>>) -> Option<OrderCommand> {
+    match order_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
 }
 
 impl PrivateClient {
-    pub async fn start(self, tx: MsgSender, mut rx: MsgReceiver) -> Result<()> {
-        let (ws, _) = connect_async(PRIVATE_WS_URL).await.unwrap();
+    /// runs until a [`Msg::SigTerm`] (or `rx` closing) is seen, reconnecting
+    /// with exponential backoff (capped, with jitter) whenever the socket
+    /// drops in the meantime. `order_cache`, `cancel_cache`, and
+    /// `pending_acks` live in this outer loop rather than inside a single
+    /// connection attempt, so in-flight acknowledgements survive a reconnect.
+    /// `shutdown_tx` is set once shutdown is observed, so a sibling
+    /// [`PublicClient`] sharing its `shutdown_rx` winds down too.
+    pub async fn start(
+        mut self,
+        tx: MsgSender,
+        mut rx: MsgReceiver,
+        shutdown_tx: watch::Sender<bool>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) -> Result<()> {
+        let apikey = self.apikey;
+        let secretkey = self.secretkey;
+        let passphrase = self.passphrase;
+        let mut active = self.channels;
+        let pending_acks = self.pending_acks;
+        let mut order_rx = self.order_rx.take();
+        let mut order_cache = HashMap::<i64, NewOrder>::new();
+        let mut cancel_cache = HashMap::<i64, CancelOrder>::new();
+        let mut amend_cache = HashMap::<i64, AmendOrder>::new();
+        let heartbeat_interval = self.heartbeat_interval;
+        let heartbeat_timeout = self.heartbeat_timeout;
+        let mut backoff = RECONNECT_INITIAL_BACKOFF;
+        loop {
+            tx.send(Msg::ConnectionState(ConnectionState::Connecting))
+                .await?;
+            let result = Self::run_connection(
+                &apikey,
+                &secretkey,
+                &passphrase,
+                &mut active,
+                &mut rx,
+                &mut order_rx,
+                &pending_acks,
+                &mut order_cache,
+                &mut cancel_cache,
+                &mut amend_cache,
+                &tx,
+                &mut backoff,
+                heartbeat_interval,
+                heartbeat_timeout,
+                &shutdown_tx,
+            )
+            .await;
+            if *shutdown_rx.borrow() {
+                return Ok(());
+            }
+            match result {
+                Ok(()) => log::warn!("okx private websocket disconnected, reconnecting"),
+                Err(e) => log::warn!("okx private websocket error: {e}, reconnecting"),
+            }
+            tx.send(Msg::ConnectionState(ConnectionState::Disconnected))
+                .await?;
+            let wait = with_jitter(backoff);
+            log::info!("reconnecting to okx private websocket in {:?}", wait);
+            tx.send(Msg::ConnectionState(ConnectionState::Reconnecting))
+                .await?;
+            tokio::time::sleep(wait).await;
+            backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn run_connection(
+        apikey: &str,
+        secretkey: &str,
+        passphrase: &str,
+        active: &mut Vec<WsChannelArg>,
+        rx: &mut MsgReceiver,
+        order_rx: &mut Option<mpsc::Receiver<OrderCommand>>,
+        pending_acks: &Arc<Mutex<HashMap<i64, oneshot::Sender<ExecutionReport>>>>,
+        order_cache: &mut HashMap<i64, NewOrder>,
+        cancel_cache: &mut HashMap<i64, CancelOrder>,
+        amend_cache: &mut HashMap<i64, AmendOrder>,
+        tx: &MsgSender,
+        backoff: &mut Duration,
+        heartbeat_interval: Duration,
+        heartbeat_timeout: Duration,
+        shutdown_tx: &watch::Sender<bool>,
+    ) -> Result<()> {
+        let (ws, _) = connect_async(PRIVATE_WS_URL).await?;
         let (mut write, mut read) = ws.split();
         log::info!("connected to private websocket");
+        tx.send(Msg::ConnectionState(ConnectionState::Connected))
+            .await?;
         let timestamp = chrono::Utc::now().timestamp().to_string();
-        let sign = get_sign(&timestamp, "GET", "/users/self/verify", "", &self.secretkey);
+        let sign = get_sign(&timestamp, "GET", "/users/self/verify", "", secretkey);
         let login_cmd = WsCommand::Login {
             args: vec![WsAccount {
-                api_key: self.apikey,
-                passphrase: self.passphrase,
+                api_key: apikey.to_owned(),
+                passphrase: passphrase.to_owned(),
                 timestamp,
                 sign,
             }],
@@ -237,32 +758,38 @@ impl PrivateClient {
             .send(Message::Text(serde_json::to_string(&login_cmd)?))
             .await?;
         log::info!("sent login request");
-        let sub_cmd = WsCommand::Subscribe {
-            args: self.channels,
-        };
-        let mut order_cache = HashMap::<i64, NewOrder>::new();
-        let mut cancel_cache = HashMap::<i64, CancelOrder>::new();
+        let mut hb_tick = tokio::time::interval(heartbeat_interval);
+        hb_tick.tick().await;
+        let mut last_pong = tokio::time::Instant::now();
         loop {
             tokio::select! {
+                _ = hb_tick.tick() => {
+                    if last_pong.elapsed() > heartbeat_timeout {
+                        return Err(anyhow::anyhow!(
+                            "no pong received within {:?}",
+                            heartbeat_timeout
+                        ));
+                    }
+                    write.send(Message::Text("ping".to_string())).await?;
+                }
                 m = rx.recv() => {
-                    if let Some(m) = m {
-                        let id = Utc::now().timestamp();
-                        match m {
+                    match m {
+                        Some(Msg::SigTerm) | None => {
+                            let _ = write.send(Message::Close(None)).await;
+                            tx.send(Msg::ConnectionState(ConnectionState::Disconnected))
+                                .await?;
+                            let _ = shutdown_tx.send(true);
+                            return Ok(());
+                        }
+                        Some(m) => match m {
                             Msg::NewOrder(o) => {
-                                let ws_order = WsOrder {
-                                    inst_id: parser::inst_to_str(&o.inst),
-                                    td_mode: parser::td_mode_to_str(&o.td_mode).to_owned(),
-                                    cl_ord_id: id.to_string(),
-                                    side: parser::side_to_str(&o.side).to_owned(),
-                                    ord_type: parser::ord_type_to_str(&o.ord_type).to_owned(),
-                                    sz: o.sz,
-                                    px: o.px,
-                                };
-                                let cmd = WsCommand::Order { id: id.to_string(), args: vec![ws_order] };
+                                let id = o.cl_ord_id;
+                                let cmd = WsCommand::Order { id: id.to_string(), args: vec![build_ws_order(&o)] };
                                 write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
                                 order_cache.insert(id, o);
                             },
                             Msg::CancelOrder(c) => {
+                                let id = c.cl_ord_id;
                                 let ws_cancel = WsCancel {
                                     inst_id: parser::inst_to_str(&c.inst),
                                     cl_ord_id: c.cl_ord_id.to_string(),
@@ -271,46 +798,189 @@ impl PrivateClient {
                                 write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
                                 cancel_cache.insert(id, c);
                             },
+                            Msg::AmendOrder(a) => {
+                                let id = a.cl_ord_id;
+                                let cmd = WsCommand::AmendOrder { id: id.to_string(), args: vec![build_ws_amend(&a)] };
+                                write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                                amend_cache.insert(id, a);
+                            },
+                            Msg::NewOrderBatch(orders) => {
+                                for chunk in orders.chunks(ORDER_BATCH_MAX) {
+                                    let id = chunk[0].cl_ord_id;
+                                    let args = chunk.iter().map(build_ws_order).collect();
+                                    let cmd = WsCommand::BatchOrders { id: id.to_string(), args };
+                                    write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                                    for o in chunk {
+                                        order_cache.insert(o.cl_ord_id, o.clone());
+                                    }
+                                }
+                            },
+                            Msg::CancelOrderBatch(orders) => {
+                                for chunk in orders.chunks(ORDER_BATCH_MAX) {
+                                    let id = chunk[0].cl_ord_id;
+                                    let args = chunk
+                                        .iter()
+                                        .map(|c| WsCancel {
+                                            inst_id: parser::inst_to_str(&c.inst),
+                                            cl_ord_id: c.cl_ord_id.to_string(),
+                                        })
+                                        .collect();
+                                    let cmd = WsCommand::BatchCancelOrders { id: id.to_string(), args };
+                                    write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                                    for c in chunk {
+                                        cancel_cache.insert(c.cl_ord_id, c.clone());
+                                    }
+                                }
+                            },
                             _ => (),
+                        },
+                    }
+                },
+                cmd = recv_order(order_rx) => {
+                    let Some(cmd) = cmd else { continue };
+                    let mut new_orders = Vec::new();
+                    let mut cancels = Vec::new();
+                    match cmd {
+                        OrderCommand::NewOrder(o, ack_tx) => new_orders.push((o, ack_tx)),
+                        OrderCommand::CancelOrder(c) => cancels.push(c),
+                        OrderCommand::AmendOrder(a, ack_tx) => {
+                            let id = a.cl_ord_id;
+                            let cmd = WsCommand::AmendOrder { id: id.to_string(), args: vec![build_ws_amend(&a)] };
+                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                            pending_acks.lock().unwrap().insert(id, ack_tx);
+                            amend_cache.insert(id, a);
+                        }
+                    }
+                    // an amend never starts a batch window on its own, but a new
+                    // order or cancel does: give a few more milliseconds for
+                    // siblings arriving in the same burst to join it into one
+                    // `batch-orders`/`batch-cancel-orders` frame.
+                    if !new_orders.is_empty() || !cancels.is_empty() {
+                        let deadline = tokio::time::Instant::now() + ORDER_BATCH_WINDOW;
+                        while new_orders.len() + cancels.len() < ORDER_BATCH_MAX {
+                            tokio::select! {
+                                _ = tokio::time::sleep_until(deadline) => break,
+                                more = recv_order(order_rx) => {
+                                    match more {
+                                        Some(OrderCommand::NewOrder(o, ack_tx)) => new_orders.push((o, ack_tx)),
+                                        Some(OrderCommand::CancelOrder(c)) => cancels.push(c),
+                                        Some(OrderCommand::AmendOrder(a, ack_tx)) => {
+                                            let id = a.cl_ord_id;
+                                            let cmd = WsCommand::AmendOrder { id: id.to_string(), args: vec![build_ws_amend(&a)] };
+                                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                                            pending_acks.lock().unwrap().insert(id, ack_tx);
+                                            amend_cache.insert(id, a);
+                                        }
+                                        None => break,
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    match new_orders.len() {
+                        0 => (),
+                        1 => {
+                            let (o, ack_tx) = new_orders.pop().unwrap();
+                            let id = o.cl_ord_id;
+                            let cmd = WsCommand::Order { id: id.to_string(), args: vec![build_ws_order(&o)] };
+                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                            pending_acks.lock().unwrap().insert(id, ack_tx);
+                            order_cache.insert(id, o);
+                        }
+                        _ => {
+                            let id = new_orders[0].0.cl_ord_id;
+                            let args = new_orders.iter().map(|(o, _)| build_ws_order(o)).collect();
+                            let cmd = WsCommand::BatchOrders { id: id.to_string(), args };
+                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                            for (o, ack_tx) in new_orders {
+                                pending_acks.lock().unwrap().insert(o.cl_ord_id, ack_tx);
+                                order_cache.insert(o.cl_ord_id, o);
+                            }
+                        }
+                    }
+                    match cancels.len() {
+                        0 => (),
+                        1 => {
+                            let c = cancels.pop().unwrap();
+                            let id = c.cl_ord_id;
+                            let ws_cancel = WsCancel {
+                                inst_id: parser::inst_to_str(&c.inst),
+                                cl_ord_id: c.cl_ord_id.to_string(),
+                            };
+                            let cmd = WsCommand::CancelOrder { id: id.to_string(), args: vec![ws_cancel] };
+                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                            cancel_cache.insert(id, c);
+                        }
+                        _ => {
+                            let id = cancels[0].cl_ord_id;
+                            let args = cancels
+                                .iter()
+                                .map(|c| WsCancel {
+                                    inst_id: parser::inst_to_str(&c.inst),
+                                    cl_ord_id: c.cl_ord_id.to_string(),
+                                })
+                                .collect();
+                            let cmd = WsCommand::BatchCancelOrders { id: id.to_string(), args };
+                            write.send(Message::Text(serde_json::to_string(&cmd)?)).await?;
+                            for c in cancels {
+                                cancel_cache.insert(c.cl_ord_id, c);
+                            }
                         }
                     }
                 },
                 m = read.next() => {
-                    if let Some(m) = m{
+                    let Some(m) = m else { return Ok(()) };
+                    {
                         if let Message::Text(m) = m? {
+                            if m == "pong" {
+                                last_pong = tokio::time::Instant::now();
+                                continue;
+                            }
                             let ws_msg: WsMessage = serde_json::from_str(&m)?;
                             match ws_msg {
                                 WsMessage::LoginResult(res) => {
                                     if res.is_ok() {
                                         log::info!("okx private websocket login succeed");
+                                        tx.send(Msg::ConnectionState(ConnectionState::LoggedIn))
+                                            .await?;
+                                        let sub_cmd = WsCommand::Subscribe { args: active.clone() };
                                         write
                                             .send(Message::Text(serde_json::to_string(&sub_cmd)?))
                                             .await?;
+                                        *backoff = RECONNECT_INITIAL_BACKOFF;
                                     } else {
                                         log::error!("okx private websocket login failed: {}", res.msg);
                                     }
                                 }
-                                WsMessage::Data { data, arg } => {
+                                WsMessage::Data { data, arg, .. } => {
                                     match arg.channel {
                                         WsChannel::Orders => {
                                             for d in data {
-                                                if let Ok(m) = parser::parse_order(&d) {
-                                                    tx.send(Msg::ExecutionReport(m)).await?;
+                                                match parser::parse_order(&d) {
+                                                    Ok(m) => {
+                                                        if let Some(ack) = pending_acks.lock().unwrap().remove(&m.cl_ord_id) {
+                                                            let _ = ack.send(m.clone());
+                                                        }
+                                                        tx.send(Msg::ExecutionReport(m)).await?;
+                                                    }
+                                                    Err(e) => log::error!("dropping malformed order report: {e}"),
                                                 }
                                             }
                                         }
                                         WsChannel::Positions => {
                                             for d in data {
-                                                if let Ok(m) = parser::parse_position(&d) {
-                                                    tx.send(Msg::PositionReport(m)).await?;
+                                                match parser::parse_position(&d) {
+                                                    Ok(m) => tx.send(Msg::PositionReport(m)).await?,
+                                                    Err(e) => log::error!("dropping malformed position report: {e}"),
                                                 }
                                             }
                                         }
                                         WsChannel::BalanceAndPosition => {
                                             for d in data{
                                                 for b in d["balData"].as_array().unwrap(){
-                                                    if let Ok(m) = parser::parse_balance_and_position(b){
-                                                        tx.send(Msg::BalanceReport(m)).await?;
+                                                    match parser::parse_balance_and_position(b) {
+                                                        Ok(m) => tx.send(Msg::BalanceReport(m)).await?,
+                                                        Err(e) => log::error!("dropping malformed balance report: {e}"),
                                                     }
                                                 }
                                             }
@@ -347,6 +1017,9 @@ impl PrivateClient {
                                                     lever: 0.0,
                                                     fee: 0.0,
                                                 };
+                                                if let Some(ack) = pending_acks.lock().unwrap().remove(&m.cl_ord_id) {
+                                                    let _ = ack.send(m.clone());
+                                                }
                                                 tx.send(Msg::ExecutionReport(m)).await?;
                                             }else{
                                                 log::warn!("order not found: {}", res.id);
@@ -366,6 +1039,99 @@ impl PrivateClient {
                                                 log::warn!("cancel not found: {}", res.id);
                                             }
                                         },
+                                        WsOp::AmendOrder => {
+                                            if res.is_ok() {
+                                                let _ = amend_cache.remove(&res.id);
+                                            } else if let Some(a) = amend_cache.remove(&res.id) {
+                                                log::error!("amend order {} failed: {}", res.id, res.msg);
+                                                // the original order's side/type aren't part of an
+                                                // amend request; fall back to whatever's still in
+                                                // order_cache for them, since the order being amended
+                                                // is ordinarily still live there.
+                                                let (ord_type, side, px, sz) = order_cache
+                                                    .get(&a.cl_ord_id)
+                                                    .map(|o| (o.ord_type, o.side, o.px, o.sz))
+                                                    .unwrap_or((OrdType::Market, Side::Buy, 0.0, 0.0));
+                                                let ts = chrono::Utc::now().naive_utc();
+                                                let m = ExecutionReport{
+                                                    c_time: ts,
+                                                    u_time: ts,
+                                                    inst: a.inst,
+                                                    ord_id: 0,
+                                                    cl_ord_id: a.cl_ord_id,
+                                                    px: a.new_px.unwrap_or(px),
+                                                    sz: a.new_sz.unwrap_or(sz),
+                                                    notional_usd: 0.0,
+                                                    ord_type,
+                                                    side,
+                                                    fill_px: 0.0,
+                                                    fill_sz: 0.0,
+                                                    acc_fill_sz: 0.0,
+                                                    avg_px: 0.0,
+                                                    state: OrdState::Rejected,
+                                                    lever: 0.0,
+                                                    fee: 0.0,
+                                                };
+                                                if let Some(ack) = pending_acks.lock().unwrap().remove(&m.cl_ord_id) {
+                                                    let _ = ack.send(m.clone());
+                                                }
+                                                tx.send(Msg::ExecutionReport(m)).await?;
+                                            } else {
+                                                log::warn!("amend not found: {}", res.id);
+                                            }
+                                        },
+                                        WsOp::BatchOrders => {
+                                            for item in &res.data {
+                                                let Ok(cl_ord_id) = item.cl_ord_id.parse::<i64>() else { continue };
+                                                if item.is_ok() {
+                                                    let _ = order_cache.remove(&cl_ord_id);
+                                                } else if let Some(o) = order_cache.remove(&cl_ord_id) {
+                                                    let ts = chrono::Utc::now().naive_utc();
+                                                    let m = ExecutionReport{
+                                                        c_time: ts,
+                                                        u_time: ts,
+                                                        inst: o.inst,
+                                                        ord_id: 0,
+                                                        cl_ord_id: o.cl_ord_id,
+                                                        px: o.px,
+                                                        sz: o.sz,
+                                                        notional_usd: 0.0,
+                                                        ord_type: o.ord_type,
+                                                        side: o.side,
+                                                        fill_px: 0.0,
+                                                        fill_sz: 0.0,
+                                                        acc_fill_sz: 0.0,
+                                                        avg_px: 0.0,
+                                                        state: OrdState::Rejected,
+                                                        lever: 0.0,
+                                                        fee: 0.0,
+                                                    };
+                                                    if let Some(ack) = pending_acks.lock().unwrap().remove(&m.cl_ord_id) {
+                                                        let _ = ack.send(m.clone());
+                                                    }
+                                                    tx.send(Msg::ExecutionReport(m)).await?;
+                                                } else {
+                                                    log::warn!("batch order item not found: {}", item.cl_ord_id);
+                                                }
+                                            }
+                                        },
+                                        WsOp::BatchCancelOrders => {
+                                            for item in &res.data {
+                                                let Ok(cl_ord_id) = item.cl_ord_id.parse::<i64>() else { continue };
+                                                if item.is_ok() {
+                                                    let _ = order_cache.remove(&cl_ord_id);
+                                                } else if let Some(c) = cancel_cache.remove(&cl_ord_id) {
+                                                    let m = CancelReject{
+                                                        inst: c.inst,
+                                                        cl_ord_id: c.cl_ord_id,
+                                                        u_time: chrono::Utc::now().naive_utc(),
+                                                    };
+                                                    tx.send(Msg::CancelReject(m)).await?;
+                                                } else {
+                                                    log::warn!("batch cancel item not found: {}", item.cl_ord_id);
+                                                }
+                                            }
+                                        },
                                     }
                                 },
                             }
@@ -576,4 +1342,92 @@ mod tests {
             _ => panic!("unexpected message"),
         };
     }
+
+    #[test]
+    fn deserialize_batch_orders_msg_demuxes_per_item() {
+        let s = r#"
+        {
+            "id": "1723",
+            "op": "batch-orders",
+            "data": [
+                {
+                    "clOrdId": "1",
+                    "ordId": "2510789768709121",
+                    "sCode": "0",
+                    "sMsg": ""
+                },
+                {
+                    "clOrdId": "2",
+                    "ordId": "",
+                    "sCode": "5XXXX",
+                    "sMsg": "Insufficient balance"
+                }
+            ],
+            "code": "0",
+            "msg": ""
+        }"#;
+        let m: WsMessage = serde_json::from_str(s).unwrap();
+        match m {
+            WsMessage::TradeResult(res) => {
+                assert!(res.is_ok());
+                assert_eq!(res.op, WsOp::BatchOrders);
+                assert_eq!(res.data.len(), 2);
+                assert!(res.data[0].is_ok());
+                assert!(!res.data[1].is_ok());
+            }
+            _ => panic!("unexpected message"),
+        };
+    }
+
+    #[test]
+    fn deserialize_batch_cancel_orders_msg_demuxes_per_item() {
+        let s = r#"
+        {
+            "id": "1724",
+            "op": "batch-cancel-orders",
+            "data": [
+                {
+                    "clOrdId": "1",
+                    "ordId": "2510789768709121",
+                    "sCode": "0",
+                    "sMsg": ""
+                },
+                {
+                    "clOrdId": "2",
+                    "ordId": "",
+                    "sCode": "5XXXX",
+                    "sMsg": "Order not exist"
+                }
+            ],
+            "code": "0",
+            "msg": ""
+        }"#;
+        let m: WsMessage = serde_json::from_str(s).unwrap();
+        match m {
+            WsMessage::TradeResult(res) => {
+                assert!(res.is_ok());
+                assert_eq!(res.op, WsOp::BatchCancelOrders);
+                assert_eq!(res.data.len(), 2);
+                assert!(res.data[0].is_ok());
+                assert!(!res.data[1].is_ok());
+            }
+            _ => panic!("unexpected message"),
+        };
+    }
+
+    #[test]
+    fn order_book_apply_snapshot_rejects_malformed_level_instead_of_panicking() {
+        let s = r#"{"bids": [["not-a-number", "1"]], "asks": []}"#;
+        let d: Value = serde_json::from_str(s).unwrap();
+        let mut book = OrderBook::default();
+        assert!(book.apply_snapshot(&d).is_err());
+    }
+
+    #[test]
+    fn order_book_apply_snapshot_rejects_malformed_size_instead_of_panicking() {
+        let s = r#"{"bids": [["100.0", "not-a-number"]], "asks": []}"#;
+        let d: Value = serde_json::from_str(s).unwrap();
+        let mut book = OrderBook::default();
+        assert!(book.apply_snapshot(&d).is_err());
+    }
 }
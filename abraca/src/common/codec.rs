@@ -0,0 +1,205 @@
+use crate::common::defs::{Ccy, Exch, Result, Side};
+use crate::common::msgs::{Depth, Ticker, Trade};
+use anyhow::anyhow;
+use chrono::NaiveDateTime;
+
+/// a fixed 32-byte little-endian market-data record, for high-throughput
+/// on-disk/wire capture. The stride never changes, so consumers can `mmap`
+/// a file of these and index record `i` at byte offset `i * 32`, with no
+/// framing header.
+///
+/// layout:
+/// - byte 0: exchange code ([`Exch`] as `u8`)
+/// - byte 1: base currency code ([`Ccy`] as `u8`)
+/// - byte 2: quote currency code ([`Ccy`] as `u8`)
+/// - byte 3: side (0 = none, 1 = buy, 2 = sell)
+/// - bytes 4..8: `u32` nanosecond offset of `exch_time` before `recv_time`
+///   (0 means `exch_time` wasn't recorded)
+/// - bytes 8..16: `u64` `recv_time`, nanoseconds since the Unix epoch
+/// - bytes 16..24: `f64` price
+/// - bytes 24..32: `f64` size
+///
+/// a [`Record`] has no instrument-type field, so it can't distinguish e.g.
+/// spot `BTC-USDT` from a `BTC-USDT` future; capture into separate files
+/// per instrument type if that matters downstream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub exch: Exch,
+    pub base_ccy: Ccy,
+    pub quote_ccy: Ccy,
+    pub side: Option<Side>,
+    pub exch_time: Option<NaiveDateTime>,
+    pub recv_time: NaiveDateTime,
+    pub px: f64,
+    pub sz: f64,
+}
+
+impl Record {
+    pub fn encode(&self) -> [u8; 32] {
+        let mut buf = [0u8; 32];
+        buf[0] = self.exch.into();
+        buf[1] = self.base_ccy.clone() as u8;
+        buf[2] = self.quote_ccy.clone() as u8;
+        buf[3] = match self.side {
+            None => 0,
+            Some(Side::Buy) => 1,
+            Some(Side::Sell) => 2,
+        };
+        let recv_ns = naive_datetime_to_nanos(self.recv_time);
+        let offset_ns = self
+            .exch_time
+            .and_then(|t| u32::try_from(recv_ns - naive_datetime_to_nanos(t)).ok())
+            .unwrap_or(0);
+        buf[4..8].copy_from_slice(&offset_ns.to_le_bytes());
+        buf[8..16].copy_from_slice(&(recv_ns as u64).to_le_bytes());
+        buf[16..24].copy_from_slice(&self.px.to_le_bytes());
+        buf[24..32].copy_from_slice(&self.sz.to_le_bytes());
+        buf
+    }
+
+    pub fn decode(buf: &[u8; 32]) -> Result<Self> {
+        let exch = Exch::try_from(buf[0])?;
+        let base_ccy = Ccy::try_from(buf[1])?;
+        let quote_ccy = Ccy::try_from(buf[2])?;
+        let side = match buf[3] {
+            0 => None,
+            1 => Some(Side::Buy),
+            2 => Some(Side::Sell),
+            b => return Err(anyhow!("unrecognized side code {b}")),
+        };
+        let offset_ns = u32::from_le_bytes(buf[4..8].try_into().unwrap());
+        let recv_ns = u64::from_le_bytes(buf[8..16].try_into().unwrap()) as i64;
+        let recv_time = nanos_to_naive_datetime(recv_ns)?;
+        let exch_time = match offset_ns {
+            0 => None,
+            _ => Some(nanos_to_naive_datetime(recv_ns - offset_ns as i64)?),
+        };
+        let px = f64::from_le_bytes(buf[16..24].try_into().unwrap());
+        let sz = f64::from_le_bytes(buf[24..32].try_into().unwrap());
+        Ok(Record {
+            exch,
+            base_ccy,
+            quote_ccy,
+            side,
+            exch_time,
+            recv_time,
+            px,
+            sz,
+        })
+    }
+}
+
+fn naive_datetime_to_nanos(t: NaiveDateTime) -> i64 {
+    t.timestamp_nanos_opt().unwrap_or_default()
+}
+
+fn nanos_to_naive_datetime(ns: i64) -> Result<NaiveDateTime> {
+    NaiveDateTime::from_timestamp_opt(ns.div_euclid(1_000_000_000), ns.rem_euclid(1_000_000_000) as u32)
+        .ok_or_else(|| anyhow!("nanosecond timestamp {ns} out of range"))
+}
+
+impl From<&Trade> for Record {
+    fn from(t: &Trade) -> Self {
+        Record {
+            exch: t.inst.exch,
+            base_ccy: t.inst.base_ccy.clone(),
+            quote_ccy: t.inst.quote_ccy.clone(),
+            side: Some(t.side),
+            exch_time: Some(t.exch_time),
+            recv_time: t.recv_time,
+            px: t.px,
+            sz: t.sz,
+        }
+    }
+}
+
+/// lossy: a [`Ticker`] carries last/best-bid/best-ask prices and sizes, but
+/// a [`Record`] only has room for one (price, size) pair. Keeps the last
+/// traded price/size, the closest analogue to a [`Trade`]; capture the
+/// quote separately (e.g. via [`Depth`]) if it needs to survive.
+impl From<&Ticker> for Record {
+    fn from(t: &Ticker) -> Self {
+        Record {
+            exch: t.inst.exch,
+            base_ccy: t.inst.base_ccy.clone(),
+            quote_ccy: t.inst.quote_ccy.clone(),
+            side: None,
+            exch_time: Some(t.exch_time),
+            recv_time: t.recv_time,
+            px: t.last,
+            sz: t.last_sz,
+        }
+    }
+}
+
+/// lossy: a [`Depth`] carries 5 levels on each side, but a [`Record`] only
+/// has room for one (price, size) pair. Keeps the top-of-book bid; encode
+/// `d.asks[0]` separately (with `side: Some(Side::Sell)`) to also capture
+/// the ask side.
+impl From<&Depth> for Record {
+    fn from(d: &Depth) -> Self {
+        Record {
+            exch: d.inst.exch,
+            base_ccy: d.inst.base_ccy.clone(),
+            quote_ccy: d.inst.quote_ccy.clone(),
+            side: Some(Side::Buy),
+            exch_time: Some(d.exch_time),
+            recv_time: d.recv_time,
+            px: d.bids[0].0,
+            sz: d.bids[0].1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::defs::{Inst, InstType};
+
+    fn inst() -> Inst {
+        Inst {
+            exch: Exch::Okx,
+            base_ccy: Ccy::BTC,
+            quote_ccy: Ccy::USDT,
+            inst_type: InstType::Spot,
+        }
+    }
+
+    #[test]
+    fn trade_round_trips_through_record() {
+        let exch_time = NaiveDateTime::from_timestamp_opt(1_700_000_000, 123_000_000).unwrap();
+        let recv_time = NaiveDateTime::from_timestamp_opt(1_700_000_000, 125_500_000).unwrap();
+        let trade = Trade {
+            inst: inst(),
+            exch_time,
+            recv_time,
+            side: Side::Sell,
+            px: 43_210.5,
+            sz: 0.25,
+        };
+        let record = Record::from(&trade);
+        let decoded = Record::decode(&record.encode()).unwrap();
+        assert_eq!(decoded, record);
+        assert_eq!(decoded.side, Some(Side::Sell));
+        assert_eq!(decoded.exch_time, Some(exch_time));
+        assert_eq!(decoded.recv_time, recv_time);
+        assert_eq!(decoded.px, 43_210.5);
+        assert_eq!(decoded.sz, 0.25);
+    }
+
+    #[test]
+    fn record_without_exch_time_round_trips() {
+        let record = Record {
+            exch: Exch::Okx,
+            base_ccy: Ccy::BTC,
+            quote_ccy: Ccy::USDT,
+            side: None,
+            exch_time: None,
+            recv_time: NaiveDateTime::from_timestamp_opt(1_700_000_000, 0).unwrap(),
+            px: 1.0,
+            sz: 2.0,
+        };
+        let decoded = Record::decode(&record.encode()).unwrap();
+        assert_eq!(decoded, record);
+    }
+}
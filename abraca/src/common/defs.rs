@@ -1,13 +1,29 @@
 use abraca_macros::clike_enum;
+use anyhow::anyhow;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumString};
 
 pub type Result<T> = anyhow::Result<T>;
 
 clike_enum!(Ccy, "fixtures/ccys.txt");
 
+impl TryFrom<u8> for Ccy {
+    type Error = anyhow::Error;
+
+    /// the inverse of `ccy.clone() as u8`. `Ccy` is `#[repr(u8)]`, but since
+    /// its variants are generated from `fixtures/ccys.txt` at compile time,
+    /// there's no fixed match arm list to write by hand, so this looks the
+    /// byte up against every variant instead.
+    fn try_from(b: u8) -> Result<Self> {
+        Ccy::iter()
+            .find(|ccy| ccy.clone() as u8 == b)
+            .ok_or_else(|| anyhow!("unrecognized currency code {b}"))
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Side {
     Buy,
@@ -21,6 +37,21 @@ pub enum OrdType {
     PostOnly,
     Fok,
     Ioc,
+    /// take-profit/stop-loss order: once `trigger_px` trades, posts a limit
+    /// order at `ord_px`. OKX's `conditional` algo order type.
+    TriggerLimit { trigger_px: f64, ord_px: f64 },
+    /// take-profit/stop-loss order: once `trigger_px` trades, posts a market
+    /// order. OKX's `trigger` algo order type.
+    TriggerMarket { trigger_px: f64 },
+    /// trails the market by a percentage (`callback_ratio`) or an absolute
+    /// amount (`callback_spread`) and fires a market order once price
+    /// reverses by that much from the best level seen since activation.
+    /// Exactly one of the two should be set. OKX's `move_order_stop` algo
+    /// order type.
+    TrailingStop {
+        callback_ratio: Option<f64>,
+        callback_spread: Option<f64>,
+    },
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Deserialize, Serialize)]
@@ -73,6 +104,27 @@ pub enum Exch {
     BinanceFutures,
 }
 
+impl From<Exch> for u8 {
+    fn from(exch: Exch) -> u8 {
+        match exch {
+            Exch::Okx => 1,
+            Exch::BinanceFutures => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Exch {
+    type Error = anyhow::Error;
+
+    fn try_from(b: u8) -> Result<Self> {
+        match b {
+            1 => Ok(Exch::Okx),
+            2 => Ok(Exch::BinanceFutures),
+            _ => Err(anyhow!("unrecognized exchange code {b}")),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Hash, Serialize, Deserialize, EnumString, Display)]
 pub enum DataType {
     Ticker,
@@ -80,6 +132,9 @@ pub enum DataType {
     OpenInterest,
     Depth,
     Trade,
+    /// full-depth order book, maintained locally from snapshot + incremental
+    /// updates (as opposed to [`DataType::Depth`], a 5-level snapshot).
+    Book,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Hash, Serialize, Deserialize)]
@@ -238,6 +293,22 @@ mod tests {
         println!("{:?}", path.parent().unwrap());
     }
 
+    #[test]
+    fn ccy_u8_round_trips() {
+        for ccy in [Ccy::BTC, Ccy::ETH, Ccy::USDT, Ccy::USD] {
+            let b = ccy.clone() as u8;
+            assert_eq!(Ccy::try_from(b).unwrap(), ccy);
+        }
+    }
+
+    #[test]
+    fn exch_u8_round_trips() {
+        for exch in [Exch::Okx, Exch::BinanceFutures] {
+            let b: u8 = exch.into();
+            assert_eq!(Exch::try_from(b).unwrap(), exch);
+        }
+    }
+
     #[test]
     fn try_into_inst_type_works() {
         assert_eq!("Spot".try_into(), Ok(InstType::Spot));
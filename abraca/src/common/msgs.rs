@@ -14,20 +14,52 @@ pub fn new_channel(buffer: usize) -> (MsgSender, MsgReceiver) {
 #[non_exhaustive]
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Msg {
+    ConnectionState(ConnectionState),
     Depth(Depth),
+    Book(Book),
     Trade(Trade),
     Ticker(Ticker),
     FundingRate(FundingRate),
     OpenInterest(OpenInterest),
     NewOrder(NewOrder),
     CancelOrder(CancelOrder),
+    AmendOrder(AmendOrder),
+    /// places every order in one batch request (e.g. OKX's
+    /// `/api/v5/trade/batch-orders`) instead of one round-trip each.
+    NewOrderBatch(Vec<NewOrder>),
+    /// cancels every order in one batch request (e.g. OKX's
+    /// `/api/v5/trade/cancel-batch-orders`) instead of one round-trip each.
+    CancelOrderBatch(Vec<CancelOrder>),
     ExecutionReport(ExecutionReport),
     CancelReject(CancelReject),
     BalanceReport(BalanceReport),
     PositionReport(PositionReport),
+    /// a planned roll of an expiring futures position into its successor
+    /// contract, surfaced by [`crate::rollover::RolloverManager`] before any
+    /// order is sent so a [`Strategy`](crate::common::traits::Strategy) gets
+    /// a chance to veto it.
+    Rollover(Rollover),
     SigTerm,
 }
 
+/// lifecycle of an [`Api`](crate::common::traits::Api)'s connection to the
+/// exchange, pushed on every transition so a [`Strategy`](crate::common::traits::Strategy)
+/// can halt trading while the feed is down and resume once it recovers,
+/// instead of inferring connection health from the absence of other messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum ConnectionState {
+    /// dialing the websocket
+    Connecting,
+    /// socket established; for a private client, login hasn't completed yet
+    Connected,
+    /// private client only: login accepted, trading requests can be sent
+    LoggedIn,
+    /// socket dropped, or a graceful shutdown was requested
+    Disconnected,
+    /// backing off before the next connection attempt
+    Reconnecting,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Depth {
     /// instrument id
@@ -42,6 +74,23 @@ pub struct Depth {
     pub bids: [(f64, f64); 5],
 }
 
+/// a full local order book, maintained from an exchange's snapshot +
+/// incremental update stream (e.g. OKX's `books` channel). Unlike [`Depth`],
+/// level counts aren't fixed and depend on how deep the exchange feed goes.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Book {
+    /// instrument id
+    pub inst: Inst,
+    /// exchange time
+    pub exch_time: NaiveDateTime,
+    /// receive time
+    pub recv_time: NaiveDateTime,
+    /// ask prices and sizes, ascending by price
+    pub asks: Vec<(f64, f64)>,
+    /// bid prices and sizes, descending by price
+    pub bids: Vec<(f64, f64)>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Trade {
     /// instrument id
@@ -136,6 +185,18 @@ pub struct CancelOrder {
     pub cl_ord_id: i64,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AmendOrder {
+    /// instrument id
+    pub inst: Inst,
+    /// client order id of the order being amended
+    pub cl_ord_id: i64,
+    /// new order size. `None` leaves the size unchanged
+    pub new_sz: Option<f64>,
+    /// new order price. `None` leaves the price unchanged
+    pub new_px: Option<f64>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ExecutionReport {
     /// creation timestamp
@@ -213,3 +274,17 @@ pub struct PositionReport {
     /// average open price
     pub avg_px: f64,
 }
+
+/// a planned roll from an expiring `InstType::Futures` contract into its
+/// successor, built by `RolloverManager::on_position_report`. Turning it
+/// into order messages (`RolloverManager::take_ready`) is a separate step,
+/// so a strategy has a window to veto it after seeing this on the stream.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Rollover {
+    /// the expiring contract being closed out
+    pub from: Inst,
+    /// the successor contract being opened
+    pub to: Inst,
+    /// signed position size being rolled: positive is long, negative is short
+    pub pos: f64,
+}
@@ -1,11 +1,12 @@
 use crate::common::{
     defs::{Inst, Result},
     msgs::{
-        BalanceReport, CancelReject, Depth, ExecutionReport, Msg, MsgReceiver, MsgSender,
-        PositionReport, Trade,
+        AmendOrder, BalanceReport, Book, CancelOrder, CancelReject, ConnectionState, Depth,
+        ExecutionReport, Msg, MsgReceiver, MsgSender, NewOrder, PositionReport, Rollover, Trade,
     },
 };
 use chrono::NaiveDateTime;
+use std::collections::HashMap;
 
 use super::msgs::{FundingRate, OpenInterest, Ticker};
 
@@ -14,8 +15,26 @@ pub trait Api {
     async fn start(self, tx: MsgSender, rx: MsgReceiver) -> Result<()>;
 }
 
+/// lets a strategy act on the exchange instead of only observing it:
+/// submit orders, cancel them, and amend them, and learn the outcome of
+/// each request it made without having to eavesdrop on the whole
+/// [`MsgReceiver`] stream.
+pub trait Trader {
+    async fn place_order(&self, order: NewOrder) -> Result<ExecutionReport>;
+    async fn cancel_order(&self, order: CancelOrder) -> Result<()>;
+    async fn amend_order(&self, amend: AmendOrder) -> Result<ExecutionReport>;
+}
+
 pub trait Strategy {
+    #[allow(unused_variables)]
+    fn on_connection_state(&mut self, state: ConnectionState) -> Option<Msg> {
+        None
+    }
     fn on_depth(&mut self, depth: &Depth) -> Option<Msg>;
+    #[allow(unused_variables)]
+    fn on_book(&mut self, book: &Book) -> Option<Msg> {
+        None
+    }
     fn on_trade(&mut self, trade: &Trade) -> Option<Msg>;
     fn on_ticker(&mut self, ticker: &Ticker) -> Option<Msg>;
     fn on_funding_rate(&mut self, rate: &FundingRate) -> Option<Msg>;
@@ -24,13 +43,48 @@ pub trait Strategy {
     fn on_cancel_reject(&mut self, reject: &CancelReject) -> Option<Msg>;
     fn on_balance_report(&mut self, report: &BalanceReport) -> Option<Msg>;
     fn on_position_report(&mut self, report: &PositionReport) -> Option<Msg>;
+    /// a [`crate::rollover::RolloverManager`] plans to roll `rollover.from`
+    /// into `rollover.to`. Defaults to letting it proceed; a strategy that
+    /// wants to veto it should call
+    /// [`RolloverManager::veto`](crate::rollover::RolloverManager::veto)
+    /// itself, since vetoing isn't expressible as a returned [`Msg`].
+    #[allow(unused_variables)]
+    fn on_rollover(&mut self, rollover: &Rollover) -> Option<Msg> {
+        None
+    }
+}
+
+/// a read-only view of every feature's current value, handed to a feature
+/// while it's evaluated so a composite feature (e.g. a spread or a z-score)
+/// can look up the value of another feature by name.
+pub struct FeatureSnapshot<'a> {
+    pub values: &'a [Option<f64>],
+    pub id_map: &'a HashMap<String, usize>,
+}
+
+impl<'a> FeatureSnapshot<'a> {
+    pub fn value_of(&self, name: &str) -> Option<f64> {
+        self.id_map.get(name).and_then(|&idx| self.values[idx])
+    }
 }
 
 pub trait Feature {
     fn name(&self) -> &str;
     fn is_intrested(&self, inst: &Inst) -> bool;
-    fn on_depth(&mut self, depth: &Depth);
-    fn on_trade(&mut self, trade: &Trade);
+    /// named upstream features this one reads through a [`FeatureSnapshot`].
+    /// [`crate::quant::FeatureCenter::add_feature`] materializes these
+    /// recursively and evaluates them before this feature.
+    fn dependencies(&self) -> Vec<String> {
+        Vec::new()
+    }
+    fn on_depth(&mut self, depth: &Depth, snapshot: &FeatureSnapshot);
+    fn on_trade(&mut self, trade: &Trade, snapshot: &FeatureSnapshot);
+    #[allow(unused_variables)]
+    fn on_ticker(&mut self, ticker: &Ticker, snapshot: &FeatureSnapshot) {}
+    #[allow(unused_variables)]
+    fn on_open_interest(&mut self, interest: &OpenInterest, snapshot: &FeatureSnapshot) {}
+    #[allow(unused_variables)]
+    fn on_funding_rate(&mut self, rate: &FundingRate, snapshot: &FeatureSnapshot) {}
     fn value(&self) -> Option<f64>;
     fn update_time(&self) -> NaiveDateTime;
 }
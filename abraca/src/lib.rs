@@ -3,6 +3,7 @@
 pub mod api;
 pub mod common;
 pub mod quant;
+pub mod rollover;
 pub mod utils;
 pub mod prelude {
     pub use crate::common::defs::*;
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use crate::prelude::*;
 
@@ -6,6 +6,10 @@ pub struct FeatureCenter<F: FeatureLib> {
     pub lib: F,
     pub features: Vec<Box<dyn Feature>>,
     pub id_map: HashMap<String, usize>,
+    /// indices into `features`, topologically sorted so a feature is always
+    /// evaluated after everything it depends on. Recomputed whenever a
+    /// feature is added.
+    pub eval_order: Vec<usize>,
 }
 
 impl<F: FeatureLib> FeatureCenter<F> {
@@ -14,18 +18,68 @@ impl<F: FeatureLib> FeatureCenter<F> {
             lib,
             features: Vec::new(),
             id_map: HashMap::new(),
+            eval_order: Vec::new(),
         }
     }
 
-    pub fn add_feature(&mut self, name: &str) {
+    pub fn add_feature(&mut self, name: &str) -> Result<()> {
+        self.add_feature_recursive(name)?;
+        self.recompute_eval_order()
+    }
+
+    fn add_feature_recursive(&mut self, name: &str) -> Result<()> {
         if self.id_map.contains_key(name) {
-            return;
+            return Ok(());
+        }
+
+        let Some(feature) = self.lib.create_feature(name) else {
+            return Ok(());
+        };
+
+        for dep in feature.dependencies() {
+            self.add_feature_recursive(&dep)?;
+        }
+
+        self.features.push(feature);
+        self.id_map.insert(name.to_owned(), self.features.len() - 1);
+        Ok(())
+    }
+
+    /// rebuilds `eval_order` via Kahn's algorithm. Errors if the dependency
+    /// graph has a cycle.
+    fn recompute_eval_order(&mut self) -> Result<()> {
+        let n = self.features.len();
+        let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut in_degree = vec![0usize; n];
+        for (idx, feature) in self.features.iter().enumerate() {
+            for dep in feature.dependencies() {
+                if let Some(&dep_idx) = self.id_map.get(&dep) {
+                    dependents[dep_idx].push(idx);
+                    in_degree[idx] += 1;
+                }
+            }
         }
 
-        if let Some(feature) = self.lib.create_feature(name) {
-            self.features.push(feature);
-            self.id_map.insert(name.to_owned(), self.features.len() - 1);
+        let mut queue: VecDeque<usize> = (0..n).filter(|&i| in_degree[i] == 0).collect();
+        let mut eval_order = Vec::with_capacity(n);
+        while let Some(node) = queue.pop_front() {
+            eval_order.push(node);
+            for &dependent in &dependents[node] {
+                in_degree[dependent] -= 1;
+                if in_degree[dependent] == 0 {
+                    queue.push_back(dependent);
+                }
+            }
         }
+
+        if eval_order.len() < n {
+            return Err(anyhow::anyhow!(
+                "cycle detected in feature dependency graph"
+            ));
+        }
+
+        self.eval_order = eval_order;
+        Ok(())
     }
 
     pub fn values(&self) -> Vec<Option<f64>> {
@@ -33,16 +87,77 @@ impl<F: FeatureLib> FeatureCenter<F> {
     }
 
     pub fn on_depth(&mut self, depth: &Depth) {
-        self.features
-            .iter_mut()
-            .filter(|f| f.is_intrested(&depth.inst))
-            .for_each(|f| f.on_depth(depth));
+        let mut values = self.values();
+        for &idx in &self.eval_order {
+            let feature = &mut self.features[idx];
+            if feature.is_intrested(&depth.inst) {
+                let snapshot = FeatureSnapshot {
+                    values: &values,
+                    id_map: &self.id_map,
+                };
+                feature.on_depth(depth, &snapshot);
+            }
+            values[idx] = feature.value();
+        }
     }
 
     pub fn on_trade(&mut self, trade: &Trade) {
-        self.features
-            .iter_mut()
-            .filter(|f| f.is_intrested(&trade.inst))
-            .for_each(|f| f.on_trade(trade));
+        let mut values = self.values();
+        for &idx in &self.eval_order {
+            let feature = &mut self.features[idx];
+            if feature.is_intrested(&trade.inst) {
+                let snapshot = FeatureSnapshot {
+                    values: &values,
+                    id_map: &self.id_map,
+                };
+                feature.on_trade(trade, &snapshot);
+            }
+            values[idx] = feature.value();
+        }
+    }
+
+    pub fn on_ticker(&mut self, ticker: &Ticker) {
+        let mut values = self.values();
+        for &idx in &self.eval_order {
+            let feature = &mut self.features[idx];
+            if feature.is_intrested(&ticker.inst) {
+                let snapshot = FeatureSnapshot {
+                    values: &values,
+                    id_map: &self.id_map,
+                };
+                feature.on_ticker(ticker, &snapshot);
+            }
+            values[idx] = feature.value();
+        }
+    }
+
+    pub fn on_open_interest(&mut self, interest: &OpenInterest) {
+        let mut values = self.values();
+        for &idx in &self.eval_order {
+            let feature = &mut self.features[idx];
+            if feature.is_intrested(&interest.inst) {
+                let snapshot = FeatureSnapshot {
+                    values: &values,
+                    id_map: &self.id_map,
+                };
+                feature.on_open_interest(interest, &snapshot);
+            }
+            values[idx] = feature.value();
+        }
+    }
+
+    pub fn on_funding_rate(&mut self, rate: &FundingRate) {
+        let mut values = self.values();
+        for &idx in &self.eval_order {
+            let feature = &mut self.features[idx];
+            if feature.is_intrested(&rate.inst) {
+                let snapshot = FeatureSnapshot {
+                    values: &values,
+                    id_map: &self.id_map,
+                };
+                feature.on_funding_rate(rate, &snapshot);
+            }
+            values[idx] = feature.value();
+        }
     }
 }
@@ -0,0 +1,211 @@
+use crate::prelude::*;
+use chrono::{Duration, NaiveDateTime};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+/// resolves the contract that should replace `inst` once it's within the
+/// rollover window, e.g. the next weekly/monthly expiry for the same
+/// `base_ccy`/`quote_ccy`. Returns `None` if there's no standard successor
+/// (e.g. `inst` isn't an [`InstType::Futures`], or the venue hasn't listed
+/// one yet). Pluggable so each venue can supply its own expiry calendar.
+pub type NextContractFn = fn(inst: &Inst, now: NaiveDateTime) -> Option<Inst>;
+
+/// watches [`PositionReport`]s for held [`InstType::Futures`] contracts
+/// approaching expiry, resolves the successor contract via a pluggable
+/// [`NextContractFn`], and surfaces the planned roll as a vetoable
+/// [`Msg::Rollover`] before turning it into the [`NewOrder`] pair that
+/// closes the expiring leg and opens the equivalent size in the successor.
+pub struct RolloverManager {
+    next_contract: NextContractFn,
+    /// how far before expiry a held contract becomes eligible to roll.
+    window: Duration,
+    /// generates the `cl_ord_id`s stamped on the close/open order pair.
+    next_cl_ord_id: Arc<AtomicI64>,
+    /// plans already surfaced via [`Msg::Rollover`], so the same position
+    /// isn't replanned on every report and a veto can remove it before
+    /// [`Self::take_ready`] executes it. A handful of concurrently-rolling
+    /// contracts is expected, so a linear scan is fine.
+    pending: Vec<Rollover>,
+}
+
+impl RolloverManager {
+    pub fn new(next_contract: NextContractFn, window: Duration) -> Self {
+        Self {
+            next_contract,
+            window,
+            next_cl_ord_id: Arc::new(AtomicI64::new(chrono::Utc::now().timestamp_millis())),
+            pending: Vec::new(),
+        }
+    }
+
+    /// inspects a position report and, if it's a nonzero [`InstType::Futures`]
+    /// position within the rollover window with a resolvable successor that
+    /// hasn't already been planned, returns the [`Msg::Rollover`] to surface
+    /// it to strategies.
+    pub fn on_position_report(&mut self, report: &PositionReport, now: NaiveDateTime) -> Option<Msg> {
+        let InstType::Futures(exp_date) = &report.inst.inst_type else {
+            return None;
+        };
+        if report.pos == 0.0 || self.pending.iter().any(|r| r.from == report.inst) {
+            return None;
+        }
+        let expires_at = exp_date.and_hms_opt(0, 0, 0)?;
+        if expires_at - now > self.window {
+            return None;
+        }
+        let to = (self.next_contract)(&report.inst, now)?;
+        let rollover = Rollover {
+            from: report.inst.clone(),
+            to,
+            pos: report.pos,
+        };
+        self.pending.push(rollover.clone());
+        Some(Msg::Rollover(rollover))
+    }
+
+    /// drops a previously planned roll without executing it, e.g. because a
+    /// [`Strategy`] vetoed it via [`Strategy::on_rollover`].
+    pub fn veto(&mut self, from: &Inst) {
+        self.pending.retain(|r| &r.from != from);
+    }
+
+    /// removes the plan for `from`, if one is still pending, and turns it
+    /// into the market-order pair that closes the expiring leg (opposite
+    /// side) and opens the equivalent size in the successor contract (same
+    /// side). Returns `(close, open)`.
+    pub fn take_ready(&mut self, from: &Inst) -> Option<(NewOrder, NewOrder)> {
+        let idx = self.pending.iter().position(|r| &r.from == from)?;
+        let rollover = self.pending.remove(idx);
+        let side = if rollover.pos > 0.0 { Side::Buy } else { Side::Sell };
+        let close_side = match side {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        };
+        let sz = rollover.pos.abs();
+        let close = NewOrder {
+            inst: rollover.from,
+            cl_ord_id: self.next_cl_ord_id.fetch_add(1, Ordering::Relaxed),
+            side: close_side,
+            ord_type: OrdType::Market,
+            td_mode: TdMode::Cross,
+            px: 0.0,
+            sz,
+        };
+        let open = NewOrder {
+            inst: rollover.to,
+            cl_ord_id: self.next_cl_ord_id.fetch_add(1, Ordering::Relaxed),
+            side,
+            ord_type: OrdType::Market,
+            td_mode: TdMode::Cross,
+            px: 0.0,
+            sz,
+        };
+        Some((close, open))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn futures_inst(exp_date: NaiveDate) -> Inst {
+        Inst {
+            exch: Exch::Okx,
+            base_ccy: Ccy::BTC,
+            quote_ccy: Ccy::USDT,
+            inst_type: InstType::Futures(exp_date),
+        }
+    }
+
+    fn position_report(inst: Inst, pos: f64) -> PositionReport {
+        PositionReport {
+            u_time: NaiveDate::from_ymd_opt(2026, 7, 20)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap(),
+            inst,
+            mgn_mode: MgnMode::Cross,
+            pos,
+            ccy: Ccy::USDT,
+            pos_ccy: Ccy::default(),
+            avg_px: 50_000.0,
+        }
+    }
+
+    fn next_monthly(inst: &Inst, now: NaiveDateTime) -> Option<Inst> {
+        let next_month = now.date() + Duration::days(30);
+        Some(Inst {
+            inst_type: InstType::Futures(next_month),
+            ..inst.clone()
+        })
+    }
+
+    #[test]
+    fn plans_roll_within_window_and_ignores_it_afterwards() {
+        let now = NaiveDate::from_ymd_opt(2026, 7, 26)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let expiring = futures_inst(NaiveDate::from_ymd_opt(2026, 7, 27).unwrap());
+        let mut mgr = RolloverManager::new(next_monthly, Duration::hours(48));
+        let report = position_report(expiring.clone(), 1.5);
+
+        let plan = mgr.on_position_report(&report, now).unwrap();
+        let Msg::Rollover(rollover) = plan else {
+            panic!("expected Msg::Rollover");
+        };
+        assert_eq!(rollover.from, expiring);
+        assert_eq!(rollover.pos, 1.5);
+
+        assert!(mgr.on_position_report(&report, now).is_none());
+    }
+
+    #[test]
+    fn ignores_positions_outside_the_window() {
+        let now = NaiveDate::from_ymd_opt(2026, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let expiring = futures_inst(NaiveDate::from_ymd_opt(2026, 7, 27).unwrap());
+        let mut mgr = RolloverManager::new(next_monthly, Duration::hours(48));
+        let report = position_report(expiring, 1.0);
+        assert!(mgr.on_position_report(&report, now).is_none());
+    }
+
+    #[test]
+    fn take_ready_produces_closing_and_opening_orders() {
+        let now = NaiveDate::from_ymd_opt(2026, 7, 26)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let expiring = futures_inst(NaiveDate::from_ymd_opt(2026, 7, 27).unwrap());
+        let mut mgr = RolloverManager::new(next_monthly, Duration::hours(48));
+        let report = position_report(expiring.clone(), -2.0);
+        mgr.on_position_report(&report, now).unwrap();
+
+        let (close, open) = mgr.take_ready(&expiring).unwrap();
+        assert_eq!(close.inst, expiring);
+        assert_eq!(close.side, Side::Buy);
+        assert_eq!(close.sz, 2.0);
+        assert_eq!(open.side, Side::Sell);
+        assert_eq!(open.sz, 2.0);
+        assert_ne!(close.cl_ord_id, open.cl_ord_id);
+
+        assert!(mgr.take_ready(&expiring).is_none());
+    }
+
+    #[test]
+    fn veto_drops_the_pending_plan() {
+        let now = NaiveDate::from_ymd_opt(2026, 7, 26)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let expiring = futures_inst(NaiveDate::from_ymd_opt(2026, 7, 27).unwrap());
+        let mut mgr = RolloverManager::new(next_monthly, Duration::hours(48));
+        let report = position_report(expiring.clone(), 1.0);
+        mgr.on_position_report(&report, now).unwrap();
+        mgr.veto(&expiring);
+        assert!(mgr.take_ready(&expiring).is_none());
+    }
+}
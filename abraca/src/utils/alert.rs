@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// how long an identical (level, message) pair is suppressed after being
+/// sent, so e.g. a repeatedly-rejected order doesn't spam every notifier.
+pub const DEFAULT_DEDUP_WINDOW: Duration = Duration::from_secs(300);
+/// minimum gap between two alerts at the same [`AlertLevel`].
+pub const DEFAULT_RATE_LIMIT: Duration = Duration::from_secs(5);
+
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const RETRY_MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AlertLevel {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// the outcome of a failed [`Notifier::notify`] call, so [`AlertManager`]
+/// knows whether retrying has any chance of succeeding.
+#[derive(Debug)]
+pub enum NotifyError {
+    /// a network/HTTP-level failure; the same call might succeed later.
+    Transient(anyhow::Error),
+    /// the backend itself rejected the message (e.g. DingTalk's own
+    /// `errcode`); retrying with the same content will fail again.
+    Rejected(anyhow::Error),
+}
+
+/// a single alert destination, e.g. [`super::dingtalk::DingTalk`], Telegram,
+/// Slack, or a generic webhook.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, level: AlertLevel, msg: &str) -> Result<(), NotifyError>;
+}
+
+/// fans an alert out to every configured [`Notifier`], deduplicating and
+/// rate-limiting before doing so, and retrying transient per-notifier
+/// failures with exponential backoff.
+pub struct AlertManager {
+    notifiers: Vec<Arc<dyn Notifier>>,
+    dedup_window: Duration,
+    rate_limit: Duration,
+    recent: Mutex<HashMap<(AlertLevel, String), Instant>>,
+    last_sent_at_level: Mutex<HashMap<AlertLevel, Instant>>,
+}
+
+impl AlertManager {
+    pub fn new(notifiers: Vec<Arc<dyn Notifier>>, dedup_window: Duration, rate_limit: Duration) -> Self {
+        Self {
+            notifiers,
+            dedup_window,
+            rate_limit,
+            recent: Mutex::new(HashMap::new()),
+            last_sent_at_level: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// sends `msg` to every notifier, unless it's a duplicate of a recent
+    /// alert at the same `level` (within `dedup_window`) or `level` itself
+    /// was alerted on too recently (within `rate_limit`).
+    pub async fn alert(&self, level: AlertLevel, msg: &str) {
+        let now = Instant::now();
+        {
+            let mut recent = self.recent.lock().unwrap();
+            recent.retain(|_, at| now.duration_since(*at) < self.dedup_window);
+            let key = (level, msg.to_owned());
+            if recent.contains_key(&key) {
+                log::debug!("suppressing duplicate alert: {msg}");
+                return;
+            }
+            recent.insert(key, now);
+        }
+        {
+            let mut last_sent_at_level = self.last_sent_at_level.lock().unwrap();
+            if let Some(at) = last_sent_at_level.get(&level) {
+                if now.duration_since(*at) < self.rate_limit {
+                    log::debug!("rate-limiting alert at level {level:?}: {msg}");
+                    return;
+                }
+            }
+            last_sent_at_level.insert(level, now);
+        }
+        for notifier in &self.notifiers {
+            if let Err(e) = notify_with_retry(notifier.as_ref(), level, msg).await {
+                log::error!("notifier failed to send alert: {:?}", e);
+            }
+        }
+    }
+}
+
+async fn notify_with_retry(
+    notifier: &dyn Notifier,
+    level: AlertLevel,
+    msg: &str,
+) -> Result<(), NotifyError> {
+    let mut backoff = RETRY_INITIAL_BACKOFF;
+    for attempt in 1..=RETRY_MAX_ATTEMPTS {
+        match notifier.notify(level, msg).await {
+            Ok(()) => return Ok(()),
+            Err(NotifyError::Rejected(e)) => return Err(NotifyError::Rejected(e)),
+            Err(NotifyError::Transient(e)) if attempt < RETRY_MAX_ATTEMPTS => {
+                log::warn!(
+                    "notifier attempt {attempt}/{RETRY_MAX_ATTEMPTS} failed, retrying in {:?}: {}",
+                    backoff,
+                    e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!()
+}
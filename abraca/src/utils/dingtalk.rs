@@ -1,5 +1,7 @@
 use crate::common::defs::Result;
+use crate::utils::alert::{AlertLevel, NotifyError, Notifier};
 use anyhow::anyhow;
+use async_trait::async_trait;
 use reqwest::{header::HeaderMap, Client, ClientBuilder};
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -53,25 +55,31 @@ impl DingTalk {
         })
     }
 
-    pub async fn send_msg(&self, content: &str, is_markdown: bool) -> Result<()> {
+    /// sends `content` to the webhook, distinguishing a transport-level
+    /// failure (worth retrying) from DingTalk itself rejecting the message
+    /// via a nonzero `errcode` (retrying with the same content won't help).
+    async fn send_msg(&self, content: &str, is_markdown: bool) -> std::result::Result<(), NotifyError> {
         let params = if is_markdown {
             self.markdown_params(content)
         } else {
             self.text_params(content)
         };
-        let res: DingTalkResp = self
+        let resp = self
             .client
             .post(&self.webhook)
             .json(&params)
             .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?;
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| NotifyError::Transient(e.into()))?;
+        let res: DingTalkResp = resp.json().await.map_err(|e| NotifyError::Transient(e.into()))?;
         if res.errcode == 0 {
             Ok(())
         } else {
-            Err(anyhow!("DingTalk error: {}", res.errmsg))
+            Err(NotifyError::Rejected(anyhow!(
+                "DingTalk error: {}",
+                res.errmsg
+            )))
         }
     }
 
@@ -79,13 +87,29 @@ impl DingTalk {
     /// # Arguments
     /// * `s` - The text message.
     pub async fn send_text(&self, s: &str) -> Result<()> {
-        self.send_msg(s, false).await
+        self.send_msg(s, false).await.map_err(|e| match e {
+            NotifyError::Transient(e) | NotifyError::Rejected(e) => e,
+        })
     }
 
     /// `send_markdown` sends a markdown message to DingTalk.
     /// # Arguments
     /// * `s` - The markdown message.
     pub async fn send_markdown(&self, s: &str) -> Result<()> {
-        self.send_msg(s, true).await
+        self.send_msg(s, true).await.map_err(|e| match e {
+            NotifyError::Transient(e) | NotifyError::Rejected(e) => e,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for DingTalk {
+    async fn notify(&self, level: AlertLevel, msg: &str) -> std::result::Result<(), NotifyError> {
+        let prefixed = match level {
+            AlertLevel::Info => format!("[INFO] {msg}"),
+            AlertLevel::Warning => format!("[WARNING] {msg}"),
+            AlertLevel::Critical => format!("[CRITICAL] {msg}"),
+        };
+        self.send_msg(&prefixed, false).await
     }
 }
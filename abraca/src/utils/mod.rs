@@ -1,5 +1,6 @@
 use crate::prelude::*;
 
+pub mod alert;
 pub mod dingtalk;
 
 struct Wrapper<S: Strategy>(S);
@@ -9,7 +10,9 @@ impl<S: Strategy> Wrapper<S> {
         log::info!("start strategy");
         while let Some(resp) = rx.recv().await {
             if let Some(req) = match resp {
+                Msg::ConnectionState(s) => self.0.on_connection_state(s),
                 Msg::Depth(d) => self.0.on_depth(&d),
+                Msg::Book(d) => self.0.on_book(&d),
                 Msg::Trade(d) => self.0.on_trade(&d),
                 Msg::Ticker(d) => self.0.on_ticker(&d),
                 Msg::FundingRate(d) => self.0.on_funding_rate(&d),
@@ -18,6 +21,7 @@ impl<S: Strategy> Wrapper<S> {
                 Msg::CancelReject(d) => self.0.on_cancel_reject(&d),
                 Msg::BalanceReport(d) => self.0.on_balance_report(&d),
                 Msg::PositionReport(d) => self.0.on_position_report(&d),
+                Msg::Rollover(d) => self.0.on_rollover(&d),
                 _ => None,
             } {
                 tx.send(req).await?;